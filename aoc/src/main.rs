@@ -0,0 +1,134 @@
+use std::{collections::BTreeSet, env, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+/// Build the registry of day crates the runner knows about.
+macro_rules! days {
+    ($($num:literal => $day:ident),* $(,)?) => {
+        pub const DAYS: &[u32] = &[$($num),*];
+
+        pub fn run_day(day: u32, iterations: usize) -> Option<Vec<utils::Report>> {
+            match day {
+                $($num => Some(utils::run::<$day::Solution>(
+                    stringify!($day),
+                    &["sample"],
+                    &["full"],
+                    iterations,
+                )),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+mod days;
+mod output;
+
+fn usage() -> String {
+    [
+        "usage: aoc run [--bench <N>] [--output <file.json|file.csv>] <days>...",
+        "",
+        "  <days> is a day number (5), a range (1..=25 or 1..26) or 'all'",
+        "  --bench <N>  run every phase N times and report min/median/max times",
+        "  --output     write the per-phase timings as JSON or CSV",
+    ]
+    .join("\n")
+}
+
+#[derive(Debug, Default)]
+struct Options {
+    days: BTreeSet<u32>,
+    bench: Option<usize>,
+    output: Option<(PathBuf, output::TimingsFormat)>,
+}
+
+fn main() -> Result<()> {
+    utils::log_init();
+
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        Some("run") => {
+            let options = parse_options(&args[1..])?;
+            let reports = options
+                .days
+                .iter()
+                .flat_map(|day| days::run_day(*day, options.bench.unwrap_or(1)).unwrap_or_default())
+                .collect::<Vec<_>>();
+            output::print_results(&reports);
+            if options.bench.is_some() {
+                println!();
+                output::print_benchmarks(&reports);
+            }
+            if let Some((path, format)) = &options.output {
+                output::write_timings(path, *format, &reports)?;
+            }
+            let failures = reports.iter().filter(|report| report.is_failure()).count();
+            if failures > 0 {
+                bail!("{failures} input(s) failed or did not match their expected answers");
+            }
+            Ok(())
+        }
+        _ => bail!(usage()),
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options> {
+    let mut options = Options::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bench" => {
+                let iterations = args.next().context("--bench requires a count")?;
+                let iterations = iterations
+                    .parse()
+                    .with_context(|| format!("'{iterations}' is not a count"))?;
+                if iterations == 0 {
+                    bail!("--bench requires at least 1 iteration");
+                }
+                options.bench = Some(iterations);
+            }
+            "--output" => {
+                let path = PathBuf::from(args.next().context("--output requires a file")?);
+                // Checked now rather than after every day has run
+                let format = output::TimingsFormat::from_path(&path)?;
+                options.output = Some((path, format));
+            }
+            spec => options.days.extend(parse_days(spec)?),
+        }
+    }
+    if options.days.is_empty() {
+        bail!(usage());
+    }
+    Ok(options)
+}
+
+fn parse_days(spec: &str) -> Result<BTreeSet<u32>> {
+    if spec == "all" {
+        return Ok(days::DAYS.iter().copied().collect());
+    }
+    let range = if let Some((start, end)) = spec.split_once("..=") {
+        parse_day(start)?..=parse_day(end)?
+    } else if let Some((start, end)) = spec.split_once("..") {
+        parse_day(start)?..=parse_day(end)?.saturating_sub(1)
+    } else {
+        let day = parse_day(spec)?;
+        day..=day
+    };
+    if range.is_empty() {
+        bail!("empty range of days '{spec}'");
+    }
+    let mut days = BTreeSet::new();
+    for day in range {
+        if !days::DAYS.contains(&day) {
+            bail!("no solution registered for day {day}");
+        }
+        days.insert(day);
+    }
+    Ok(days)
+}
+
+fn parse_day(day: &str) -> Result<u32> {
+    day.trim()
+        .parse()
+        .with_context(|| format!("'{day}' is not a day number"))
+}
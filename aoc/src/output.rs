@@ -0,0 +1,267 @@
+use std::{fmt::Write as _, path::Path, time::Duration};
+
+use anyhow::{bail, Context, Result};
+use utils::{Answer, Phase, Report, Timing, Verdict};
+
+/// Format an answer for a table cell. Multi-line answers are printed in full
+/// after the table, so only their size is shown.
+fn format_answer(answer: &Answer, verdict: Verdict, expected: Option<&str>) -> String {
+    let answer = match answer {
+        Answer::Lines(lines) => format!("<{} lines>", lines.len()),
+        answer => answer.to_string(),
+    };
+    match (verdict, expected) {
+        (Verdict::Fail, Some(expected)) if !expected.contains('\n') => {
+            format!("{answer} [FAIL, expected {expected}]")
+        }
+        _ => format!("{answer} [{verdict}]"),
+    }
+}
+
+/// Format a part's answer, or the first line of why it failed.
+fn format_part(answer: &Result<Answer>, verdict: Verdict, expected: Option<&str>) -> String {
+    match answer {
+        Ok(answer) => format_answer(answer, verdict, expected),
+        Err(e) => format!("error: {}", error_summary(e)),
+    }
+}
+
+fn error_summary(e: &anyhow::Error) -> String {
+    let e = format!("{e:#}");
+    e.lines().next().unwrap_or_default().to_string()
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{duration:.2?}")
+}
+
+/// Print the answers, verdicts and total (median) time of every report,
+/// followed by any multi-line answers or errors in full.
+pub fn print_results(reports: &[Report]) {
+    let rows = reports
+        .iter()
+        .map(|report| {
+            let [verdict1, verdict2] = report.verdicts();
+            let (part1, part2) = match &report.result {
+                Ok(answers) => (
+                    format_part(&answers.part1, verdict1, report.expected.part1.as_deref()),
+                    format_part(&answers.part2, verdict2, report.expected.part2.as_deref()),
+                ),
+                Err(e) => (format!("error: {}", error_summary(e)), String::new()),
+            };
+            let time = report
+                .timings
+                .as_ref()
+                .map(|timings| format_duration(timings.total()))
+                .unwrap_or_default();
+            vec![
+                report.name.clone(),
+                report.suffix.clone(),
+                part1,
+                part2,
+                time,
+            ]
+        })
+        .collect::<Vec<_>>();
+    print_table(&["Day", "Input", "Part 1", "Part 2", "Time"], &rows);
+
+    for report in reports {
+        let answers = match &report.result {
+            Ok(answers) => answers,
+            Err(e) => {
+                // Parse errors span several lines, pointing at the bad input
+                let e = format!("{e:#}");
+                if e.contains('\n') {
+                    println!();
+                    println!("{} {}: {e}", report.name, report.suffix);
+                }
+                continue;
+            }
+        };
+        for (part, answer) in [("part1", &answers.part1), ("part2", &answers.part2)] {
+            match answer {
+                Ok(Answer::Lines(lines)) => {
+                    println!();
+                    println!("{} {} {part}:", report.name, report.suffix);
+                    for line in lines {
+                        println!("{line}");
+                    }
+                }
+                Err(e) => {
+                    let e = format!("{e:#}");
+                    if e.contains('\n') {
+                        println!();
+                        println!("{} {} {part}: {e}", report.name, report.suffix);
+                    }
+                }
+                Ok(_) => {}
+            }
+        }
+    }
+}
+
+/// Print the min/median/max time of every phase of every report.
+pub fn print_benchmarks(reports: &[Report]) {
+    let mut rows = Vec::new();
+    for report in reports {
+        let Some(timings) = &report.timings else {
+            continue;
+        };
+        for phase in Phase::ALL {
+            let timing = timings.get(phase);
+            rows.push(vec![
+                report.name.clone(),
+                report.suffix.clone(),
+                phase.to_string(),
+                timing.iterations.to_string(),
+                format_duration(timing.min),
+                format_duration(timing.median),
+                format_duration(timing.max),
+            ]);
+        }
+    }
+    print_table(
+        &["Day", "Input", "Phase", "Runs", "Min", "Median", "Max"],
+        &rows,
+    );
+}
+
+fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths = header.iter().map(|h| h.len()).collect::<Vec<_>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = std::cmp::max(*width, cell.len());
+        }
+    }
+    let print_row = |row: &mut dyn Iterator<Item = &str>| {
+        let line = row
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        println!("{}", line.trim_end());
+    };
+    print_row(&mut header.iter().copied());
+    println!(
+        "{}",
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-")
+    );
+    for row in rows {
+        print_row(&mut row.iter().map(String::as_str));
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut output = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            c if c.is_control() => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+fn json_answer(answer: Option<&Answer>) -> String {
+    match answer {
+        Some(Answer::Integer(value)) => value.to_string(),
+        Some(answer) => json_string(&answer.to_string()),
+        None => "null".to_string(),
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// The file formats which timings can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimingsFormat {
+    Json,
+    Csv,
+}
+impl TimingsFormat {
+    /// The format to write `path` in, given by its extension.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(Self::Json),
+            Some("csv") => Ok(Self::Csv),
+            _ => bail!("unsupported output format {path:?}, expected a .json or .csv file"),
+        }
+    }
+}
+
+/// Write the per-phase timings of every report to `path`, in `format`. The
+/// rows for the part1 and part2 phases also hold the answer produced.
+pub fn write_timings(path: &Path, format: TimingsFormat, reports: &[Report]) -> Result<()> {
+    let rows: Vec<(&Report, Phase, &Timing, Option<&Answer>)> = reports
+        .iter()
+        .filter_map(|report| report.timings.as_ref().map(|timings| (report, timings)))
+        .flat_map(|(report, timings)| {
+            Phase::ALL.into_iter().map(move |phase| {
+                let answer = report.result.as_ref().ok().and_then(|answers| match phase {
+                    Phase::Part1 => answers.part1.as_ref().ok(),
+                    Phase::Part2 => answers.part2.as_ref().ok(),
+                    _ => None,
+                });
+                (report, phase, timings.get(phase), answer)
+            })
+        })
+        .collect();
+
+    let mut output = String::new();
+    match format {
+        TimingsFormat::Json => {
+            output.push_str("[\n");
+            for (i, (report, phase, timing, answer)) in rows.iter().enumerate() {
+                let separator = if i + 1 < rows.len() { "," } else { "" };
+                writeln!(
+                    output,
+                    "  {{\"day\": {}, \"input\": {}, \"phase\": \"{}\", \"iterations\": {}, \"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {}, \"answer\": {}}}{}",
+                    json_string(&report.name),
+                    json_string(&report.suffix),
+                    phase,
+                    timing.iterations,
+                    timing.min.as_nanos(),
+                    timing.median.as_nanos(),
+                    timing.max.as_nanos(),
+                    json_answer(*answer),
+                    separator
+                )?;
+            }
+            output.push_str("]\n");
+        }
+        TimingsFormat::Csv => {
+            output.push_str("day,input,phase,iterations,min_ns,median_ns,max_ns,answer\n");
+            for (report, phase, timing, answer) in rows {
+                writeln!(
+                    output,
+                    "{},{},{},{},{},{},{},{}",
+                    csv_field(&report.name),
+                    csv_field(&report.suffix),
+                    phase,
+                    timing.iterations,
+                    timing.min.as_nanos(),
+                    timing.median.as_nanos(),
+                    timing.max.as_nanos(),
+                    answer
+                        .map(|a| csv_field(&a.to_string()))
+                        .unwrap_or_default()
+                )?;
+            }
+        }
+    }
+    std::fs::write(path, output).with_context(|| format!("failed to write {path:?}"))
+}
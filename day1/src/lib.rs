@@ -1,7 +1,4 @@
-use std::{
-    collections::HashMap,
-    io::{BufRead, BufReader},
-};
+use std::{collections::HashMap, io::BufReader};
 
 use lazy_static::lazy_static;
 use tracing::info;
@@ -29,10 +26,11 @@ lazy_static! {
 }
 
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let mut total = 0_u64;
         for line in &self.lines {
             let first = line
@@ -43,8 +41,7 @@ impl utils::Solution for Solution {
                 .unwrap_or_default();
             let last = line
                 .chars()
-                .filter(|c| c.is_ascii_digit())
-                .last()
+                .rfind(|c| c.is_ascii_digit())
                 .unwrap_or_default()
                 .to_digit(10)
                 .unwrap_or_default();
@@ -53,7 +50,7 @@ impl utils::Solution for Solution {
         Ok(total)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let mut total = 0_u64;
         for line in &self.lines {
             let mut newline = String::new();
@@ -80,8 +77,7 @@ impl utils::Solution for Solution {
                 .unwrap();
             let last = newline
                 .chars()
-                .filter(|c| c.is_ascii_digit())
-                .last()
+                .rfind(|c| c.is_ascii_digit())
                 .unwrap()
                 .to_digit(10)
                 .unwrap();
@@ -99,11 +95,12 @@ impl Solution {
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for line in reader.lines().map_while(Result::ok) {
+        for line in utils::parse::lines(reader) {
+            let line = line?.text;
             solution.add_line(line);
         }
         Ok(solution)
@@ -1,183 +1,232 @@
-use std::io::{BufRead, BufReader};
+use anyhow::Context;
+use std::{collections::HashMap, io::BufReader};
 
-use pathfinding::directed::dijkstra::dijkstra_all;
 use tracing::debug;
-use utils::Matrix;
+use utils::{geometry::Polygon, graph::bfs_distances, DenseMatrix, Point};
 
 pub type ResultType = u64;
 
 #[derive(Debug, Default)]
 pub struct Solution {
-    grid: Matrix<char>,
+    grid: DenseMatrix<char>,
 }
 impl Solution {
-    fn set_grid(&mut self, x: usize, y: usize, c: char) {
-        self.grid.set(x as isize, y as isize, c);
+    /// The tiles connected to the pipe at `(x, y)`.
+    fn pipe_neighbours(grid: &DenseMatrix<char>, (x, y): (isize, isize)) -> Vec<(isize, isize)> {
+        let mut r = Vec::new();
+        let (north_valid, south_valid, east_valid, west_valid) = match grid.get(x, y).unwrap() {
+            '|' => (true, true, false, false),
+            '-' => (false, false, true, true),
+            'L' => (true, false, true, false),
+            'J' => (true, false, false, true),
+            '7' => (false, true, false, true),
+            'F' => (false, true, true, false),
+            'S' => (true, true, true, true),
+            _ => panic!(),
+        };
+        if north_valid {
+            match grid.get(x, y - 1) {
+                Some(c) if *c == '|' => r.push((x, y - 1)),
+                Some(c) if *c == '7' => r.push((x, y - 1)),
+                Some(c) if *c == 'F' => r.push((x, y - 1)),
+                Some(c) if *c == 'S' => r.push((x, y - 1)),
+                _ => {}
+            };
+        }
+        if south_valid {
+            match grid.get(x, y + 1) {
+                Some(c) if *c == '|' => r.push((x, y + 1)),
+                Some(c) if *c == 'L' => r.push((x, y + 1)),
+                Some(c) if *c == 'J' => r.push((x, y + 1)),
+                Some(c) if *c == 'S' => r.push((x, y + 1)),
+                _ => {}
+            };
+        }
+        if west_valid {
+            match grid.get(x - 1, y) {
+                Some(c) if *c == '-' => r.push((x - 1, y)),
+                Some(c) if *c == 'F' => r.push((x - 1, y)),
+                Some(c) if *c == 'L' => r.push((x - 1, y)),
+                Some(c) if *c == 'S' => r.push((x - 1, y)),
+                _ => {}
+            };
+        }
+        if east_valid {
+            match grid.get(x + 1, y) {
+                Some(c) if *c == '-' => r.push((x + 1, y)),
+                Some(c) if *c == 'J' => r.push((x + 1, y)),
+                Some(c) if *c == '7' => r.push((x + 1, y)),
+                Some(c) if *c == 'S' => r.push((x + 1, y)),
+                _ => {}
+            };
+        }
+        r
     }
 
-    fn calculate_loop_distances(
-        grid: &Matrix<char>,
-    ) -> std::collections::HashMap<(isize, isize), ((isize, isize), i32)> {
-        let ((start_x, start_y), _) = grid.sparse_iter().find(|(_, c)| *c == &'S').unwrap();
-        let results = dijkstra_all(&(*start_x, *start_y), |(x, y)| {
-            let x = *x;
-            let y = *y;
-            let mut r = Vec::new();
-            let (north_valid, south_valid, east_valid, west_valid) = match grid.get(x, y).unwrap() {
-                '|' => (true, true, false, false),
-                '-' => (false, false, true, true),
-                'L' => (true, false, true, false),
-                'J' => (true, false, false, true),
-                '7' => (false, true, false, true),
-                'F' => (false, true, true, false),
-                'S' => (true, true, true, true),
-                _ => panic!(),
-            };
-            if north_valid {
-                match grid.get(x, y - 1) {
-                    Some(c) if *c == '|' => r.push(((x, y - 1), 1)),
-                    Some(c) if *c == '7' => r.push(((x, y - 1), 1)),
-                    Some(c) if *c == 'F' => r.push(((x, y - 1), 1)),
-                    Some(c) if *c == 'S' => r.push(((x, y - 1), 1)),
-                    _ => {}
-                };
-            }
-            if south_valid {
-                match grid.get(x, y + 1) {
-                    Some(c) if *c == '|' => r.push(((x, y + 1), 1)),
-                    Some(c) if *c == 'L' => r.push(((x, y + 1), 1)),
-                    Some(c) if *c == 'J' => r.push(((x, y + 1), 1)),
-                    Some(c) if *c == 'S' => r.push(((x, y + 1), 1)),
-                    _ => {}
-                };
-            }
-            if west_valid {
-                match grid.get(x - 1, y) {
-                    Some(c) if *c == '-' => r.push(((x - 1, y), 1)),
-                    Some(c) if *c == 'F' => r.push(((x - 1, y), 1)),
-                    Some(c) if *c == 'L' => r.push(((x - 1, y), 1)),
-                    Some(c) if *c == 'S' => r.push(((x - 1, y), 1)),
-                    _ => {}
-                };
-            }
-            if east_valid {
-                match grid.get(x + 1, y) {
-                    Some(c) if *c == '-' => r.push(((x + 1, y), 1)),
-                    Some(c) if *c == 'J' => r.push(((x + 1, y), 1)),
-                    Some(c) if *c == '7' => r.push(((x + 1, y), 1)),
-                    Some(c) if *c == 'S' => r.push(((x + 1, y), 1)),
-                    _ => {}
-                };
-            }
-            r
-        });
-        let mut result_matrix = Matrix::new();
-        for (to, (via, cost)) in &results {
-            debug!(to = debug(to), via = debug(via), cost, "cost");
+    fn find_start(grid: &DenseMatrix<char>) -> (isize, isize) {
+        let (start, _) = grid.iter().find(|(_, c)| **c == 'S').unwrap();
+        start
+    }
+
+    fn calculate_loop_distances(grid: &DenseMatrix<char>) -> HashMap<(isize, isize), usize> {
+        let results = bfs_distances(
+            Self::find_start(grid),
+            |node| Self::pipe_neighbours(grid, *node),
+            None,
+        );
+        let mut result_matrix = DenseMatrix::new();
+        for (to, cost) in &results {
+            debug!(to = debug(to), cost, "cost");
             result_matrix.set(to.0, to.1, *cost);
         }
         results
     }
+
+    /// The tiles of the loop through the start, in order around it.
+    ///
+    /// Pipes next to the start may point at it without being part of the
+    /// loop, so each is tried in turn until a walk comes back round.
+    fn loop_tiles(grid: &DenseMatrix<char>) -> Option<Vec<(isize, isize)>> {
+        let start = Self::find_start(grid);
+        Self::pipe_neighbours(grid, start)
+            .into_iter()
+            .find_map(|first| {
+                let mut tiles = vec![start];
+                let (mut previous, mut current) = (start, first);
+                while current != start {
+                    tiles.push(current);
+                    let next = Self::pipe_neighbours(grid, current)
+                        .into_iter()
+                        .find(|n| *n != previous)?;
+                    (previous, current) = (current, next);
+                }
+                Some(tiles)
+            })
+    }
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
-        let mut solution = Self::default();
-        for (y, line) in reader.lines().map_while(Result::ok).enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                solution.set_grid(x, y, c);
-            }
-        }
-        Ok(solution)
+        let lines = utils::parse::lines(reader).collect::<Result<Vec<_>, _>>()?;
+        let grid = utils::parse::grid(
+            &lines,
+            |c| "|-LJ7F.S".contains(c).then_some(c),
+            "a pipe (|, -, L, J, 7, F, . or S)",
+        )?;
+        Ok(Self { grid })
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let results = Self::calculate_loop_distances(&self.grid);
-        let result = results.iter().max_by_key(|(_, (_, cost))| cost).unwrap();
-        Ok(result.1 .1 as ResultType)
+        let result = results.values().max().unwrap();
+        Ok(*result as ResultType)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
-        let mut expanded_grid = Matrix::new();
-        for ((x, y), c) in self.grid.sparse_iter() {
-            expanded_grid.set(x * 2, y * 2, *c);
-            match *c {
-                '|' => expanded_grid.set(x * 2, y * 2 + 1, '|'),
-                '-' => expanded_grid.set(x * 2 + 1, y * 2, '-'),
-                'L' => expanded_grid.set(x * 2 + 1, y * 2, '-'),
-                'J' => {}
-                '7' => expanded_grid.set(x * 2, y * 2 + 1, '|'),
-                'F' => {
-                    expanded_grid.set(x * 2 + 1, y * 2, '-');
-                    expanded_grid.set(x * 2, y * 2 + 1, '|')
-                }
-                'S' => {
-                    expanded_grid.set(x * 2 + 1, y * 2, '-');
-                    expanded_grid.set(x * 2, y * 2 + 1, '|')
-                }
-                _ => {}
-            }
-        }
-        let results = Self::calculate_loop_distances(&expanded_grid);
-
-        let mut loop_nodes = Matrix::new();
-        let ((start_x, start_y), _) = expanded_grid
-            .sparse_iter()
-            .find(|(_, c)| *c == &'S')
-            .unwrap();
-        loop_nodes.set(*start_x, *start_y, 1);
-        for ((x, y), _) in results {
-            loop_nodes.set(x, y, 1);
-        }
-        let (maxx, maxy) = loop_nodes.dimensions();
-        let mut probes = vec![(maxx, maxy + 1)];
-        while let Some((probex, probey)) = probes.pop() {
-            loop_nodes.set(probex, probey, 2);
-            for (dx, dy) in [
-                (0, -1),
-                (1, -1),
-                (1, 0),
-                (1, 1),
-                (0, 1),
-                (-1, 1),
-                (-1, 0),
-                (-1, 1),
-            ] {
-                let nx = probex + dx;
-                let ny = probey + dy;
-                if nx >= -1
-                    && ny >= -1
-                    && nx <= maxx + 1
-                    && ny <= maxy + 1
-                    && loop_nodes.get(nx, ny).is_none()
-                {
-                    probes.push((nx, ny))
-                }
-            }
-        }
-        let mut reachable = Matrix::new();
-        let mut count = 0;
-        for y in -1..=maxy + 1 {
-            for x in -1..=maxx + 1 {
-                if x % 2 == 0 && y % 2 == 0 {
-                    let score = match loop_nodes.get(x, y) {
-                        None if x % 2 == 0 && y % 2 == 0 => 1,
-                        _ => 0,
-                    };
-                    reachable.set(x / 2, y / 2, score);
-                    count += score;
-                }
-            }
-        }
-        Ok(count)
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
+        let tiles = Self::loop_tiles(&self.grid).context("no loop through the start")?;
+        debug!(length = tiles.len(), "loop");
+
+        // The enclosed tiles are the lattice points strictly inside the loop
+        let enclosed = Polygon::new(tiles.into_iter().map(|(x, y)| Point::new(x, y)));
+        Ok(enclosed.interior_points() as ResultType)
     }
 }
 
 #[cfg(test)]
-mod test {}
+mod test {
+    use super::*;
+    use std::io::BufReader;
+
+    use tracing_test::traced_test;
+    use utils::Solution;
+
+    fn solution(input: &[&str]) -> crate::Solution {
+        let input = input.join("\n");
+        let r = BufReader::new(input.as_bytes());
+        crate::Solution::try_from(r).unwrap()
+    }
+
+    #[test]
+    #[traced_test]
+    fn part1_sample() {
+        let s = solution(&["..F7.", ".FJ|.", "SJ.L7", "|F--J", "LJ..."]);
+        assert_eq!(
+            8 as ResultType,
+            s.answer_part1(&utils::RunContext::new()).unwrap()
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn part2_sample() {
+        let s = solution(&[
+            "...........",
+            ".S-------7.",
+            ".|F-----7|.",
+            ".||.....||.",
+            ".||.....||.",
+            ".|L-7.F-J|.",
+            ".|..|.|..|.",
+            ".L--J.L--J.",
+            "...........",
+        ]);
+        assert_eq!(
+            4 as ResultType,
+            s.answer_part2(&utils::RunContext::new()).unwrap()
+        );
+        let s = solution(&[
+            ".F----7F7F7F7F-7....",
+            ".|F--7||||||||FJ....",
+            ".||.FJ||||||||L7....",
+            "FJL7L7LJLJ||LJ.L-7..",
+            "L--J.L7...LJS7F-7L7.",
+            "....F-J..F7FJ|L7L7L7",
+            "....L7.F7||L7|.L7L7|",
+            ".....|FJLJ|FJ|F7|.LJ",
+            "....FJL-7.||.||||...",
+            "....L---J.LJ.LJLJ...",
+        ]);
+        assert_eq!(
+            8 as ResultType,
+            s.answer_part2(&utils::RunContext::new()).unwrap()
+        );
+        let s = solution(&[
+            "FF7FSF7F7F7F7F7F---7",
+            "L|LJ||||||||||||F--J",
+            "FL-7LJLJ||||||LJL-77",
+            "F--JF--7||LJLJ7F7FJ-",
+            "L---JF-JLJ.||-FJLJJ7",
+            "|F|F-JF---7F7-L7L|7|",
+            "|FFJF7L7F-JF7|JL---7",
+            "7-L-JL7||F7|L7F-7F7|",
+            "L.L7LFJ|||||FJL7||LJ",
+            "L7JLJL-JLJLJL--JLJ.L",
+        ]);
+        assert_eq!(
+            10 as ResultType,
+            s.answer_part2(&utils::RunContext::new()).unwrap()
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn stray_pipe_at_start() {
+        // The pipe above the start points at it but is not part of the loop
+        let s = solution(&[".|...", ".S-7.", ".|.|.", ".L-J.", "....."]);
+        assert_eq!(
+            4 as ResultType,
+            s.answer_part1(&utils::RunContext::new()).unwrap()
+        );
+        assert_eq!(
+            1 as ResultType,
+            s.answer_part2(&utils::RunContext::new()).unwrap()
+        );
+    }
+}
@@ -1,24 +1,23 @@
 use std::{
     cmp::{max, min},
     collections::HashSet,
-    io::{BufRead, BufReader},
+    io::BufReader,
 };
 
 use tracing::debug;
-use utils::Matrix;
+use utils::DenseMatrix;
 
 pub type ResultType = u64;
 
+/// Reads the `expansion` parameter, how many times larger each empty row
+/// and column becomes in part 2, which is 1000000 unless given. The puzzle
+/// gives 8410 for the sample with `expansion: 100`, and 1030 with 10.
 #[derive(Debug, Default)]
 pub struct Solution {
-    space: Matrix<char>,
+    space: DenseMatrix<char>,
 }
 
 impl Solution {
-    fn set_space(&mut self, x: usize, y: usize, c: char) {
-        self.space.set(x as isize, y as isize, c);
-    }
-
     fn get_expansions(&self) -> (HashSet<isize>, HashSet<isize>) {
         let (maxx, maxy) = self.space.dimensions();
 
@@ -65,11 +64,14 @@ impl Solution {
         (expand_columns, expand_rows)
     }
 
-    fn expand(&self) -> Matrix<char> {
+    fn expand(&self) -> DenseMatrix<char> {
         let (maxx, maxy) = self.space.dimensions();
         let (expand_columns, expand_rows) = self.get_expansions();
 
-        let mut new_space = Matrix::new();
+        let mut new_space = DenseMatrix::with_size(
+            self.space.width() + expand_columns.len(),
+            self.space.height() + expand_rows.len(),
+        );
         let mut dy = 0;
         for y in 0..=maxy {
             let mut dx = 0;
@@ -106,29 +108,29 @@ impl Solution {
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
-        let mut solution = Self::default();
-        for (y, line) in reader.lines().map_while(Result::ok).enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                solution.set_space(x, y, c);
-            }
-        }
-        Ok(solution)
+        let lines = utils::parse::lines(reader).collect::<Result<Vec<_>, _>>()?;
+        let space = utils::parse::grid(
+            &lines,
+            |c| matches!(c, '.' | '#').then_some(c),
+            "space (. or #)",
+        )?;
+        Ok(Self { space })
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let space = self.expand();
         let galaxies = space
-            .sparse_iter()
-            .filter(|(_, c)| *c == &'#')
+            .iter()
+            .filter(|(_, c)| **c == '#')
             .map(|(p, _)| p)
-            .cloned()
             .collect::<Vec<_>>();
         let mut total = 0;
         for (a, ga) in galaxies.iter().enumerate() {
@@ -144,16 +146,15 @@ impl utils::Solution for Solution {
         Ok(total)
     }
 
-    fn answer_part2(&self, is_full: bool) -> Self::Result {
+    fn answer_part2(&self, context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let (expand_columns, expand_rows) = self.get_expansions();
-        let expansion_factor = if is_full { 1000000 } else { 100 };
+        let expansion_factor = context.param("expansion", 1000000)?;
 
         let galaxies = self
             .space
-            .sparse_iter()
-            .filter(|(_, c)| *c == &'#')
+            .iter()
+            .filter(|(_, c)| **c == '#')
             .map(|(p, _)| p)
-            .cloned()
             .collect::<Vec<_>>();
         let mut total = 0;
         for (a, ga) in galaxies.iter().enumerate() {
@@ -209,7 +210,10 @@ mod test {
         .join("\n");
         let r = BufReader::new(input.as_bytes());
         let s = crate::Solution::try_from(r).unwrap();
-        assert_eq!(374 as ResultType, s.answer_part1(false).unwrap());
+        assert_eq!(
+            374 as ResultType,
+            s.answer_part1(&utils::RunContext::new()).unwrap()
+        );
     }
     #[test]
     #[traced_test]
@@ -229,6 +233,10 @@ mod test {
         .join("\n");
         let r = BufReader::new(input.as_bytes());
         let s = crate::Solution::try_from(r).unwrap();
-        assert_eq!(8410 as ResultType, s.answer_part2(false).unwrap());
+        assert_eq!(
+            8410 as ResultType,
+            s.answer_part2(&utils::RunContext::new().with("expansion", 100))
+                .unwrap()
+        );
     }
 }
@@ -1,7 +1,4 @@
-use std::{
-    collections::HashMap,
-    io::{BufRead, BufReader},
-};
+use std::{collections::HashMap, io::BufReader};
 
 use tracing::debug;
 
@@ -122,23 +119,28 @@ impl Solution {
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for line in reader.lines().map_while(Result::ok) {
-            let (lhs, rhs) = line.split_once(' ').unwrap();
-            let groups = rhs.split(',').map(|v| v.parse().unwrap()).collect();
+        for line in utils::parse::lines(reader) {
+            let line = line?;
+            let (lhs, rhs) = line.split_once(&line.text, " ")?;
+            if let Some((at, c)) = lhs.char_indices().find(|(_, c)| !".#?".contains(*c)) {
+                return Err(line.error(&lhs[at..at + c.len_utf8()], "a spring (., # or ?)"));
+            }
+            let groups = line.separated(rhs, ",", "a group size")?;
             solution.add_springs(lhs, groups);
         }
         Ok(solution)
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let result = self
             .data
             .iter()
@@ -147,7 +149,7 @@ impl utils::Solution for Solution {
         Ok(result)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let data = self
             .data
             .iter()
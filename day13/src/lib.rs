@@ -1,73 +1,42 @@
-use std::io::{BufRead, BufReader};
+use std::io::BufReader;
 
 use tracing::debug;
-use utils::Matrix;
+use utils::DenseMatrix;
 
 pub type ResultType = u64;
 
 #[derive(Debug, Default)]
 pub struct Solution {
-    grids: Vec<Matrix<char>>,
+    grids: Vec<DenseMatrix<char>>,
 }
 impl Solution {
-    fn add_grid(&mut self, grid: Matrix<char>) {
+    fn add_grid(&mut self, grid: DenseMatrix<char>) {
         self.grids.push(grid);
     }
 
-    fn difference(a: &str, b: &str) -> usize {
-        a.chars().zip(b.chars()).filter(|(a, b)| a != b).count()
+    fn difference(a: &[char], b: &[char]) -> usize {
+        a.iter().zip(b).filter(|(a, b)| a != b).count()
     }
 
-    fn test_vertical_mirror(grid: &Matrix<char>, mirror_after: isize, smudges: usize) -> bool {
-        let (maxx, maxy) = grid.dimensions();
-        let mut differences = 0;
-        for y in 0..=maxy {
-            let mut left = String::new();
-            let mut right = String::new();
-            let range = std::cmp::min(mirror_after + 1, maxx - mirror_after);
-            debug!(mirror_after, range, maxx, "range");
-            for i in 0..range {
-                left.push(*grid.get(mirror_after - i, y).unwrap());
-                right.push(*grid.get(mirror_after + 1 + i, y).unwrap());
-                debug!(mirror_after, i, left, right, "step");
-            }
-            differences += Self::difference(&left, &right);
-            debug!(mirror_after, range, maxx, left, right, "range");
-        }
-        differences == smudges
-    }
-
-    fn find_vertical_mirror(grid: &Matrix<char>, smudges: usize) -> Vec<isize> {
-        let (maxx, _) = grid.dimensions();
-        let mut mirrors = Vec::new();
-        for x in 0..maxx {
-            if Self::test_vertical_mirror(grid, x, smudges) {
-                debug!(x, "vertical");
-                mirrors.push(x);
-            }
-        }
-        mirrors
-    }
-    fn test_horizontal_mirror(grid: &Matrix<char>, mirror_after: isize, smudges: usize) -> bool {
-        let (maxx, maxy) = grid.dimensions();
-        let mut differences = 0;
-        for x in 0..=maxx {
-            let mut top = String::new();
-            let mut bottom = String::new();
-            let range = std::cmp::min(mirror_after + 1, maxy - mirror_after);
-            debug!(mirror_after, range, maxy, "range");
-            for i in 0..range {
-                top.push(*grid.get(x, mirror_after - i).unwrap());
-                bottom.push(*grid.get(x, mirror_after + 1 + i).unwrap());
-                debug!(mirror_after, i, top, bottom, "step");
-            }
-            differences += Self::difference(&top, &bottom);
-            debug!(mirror_after, range, maxx, top, bottom, "range");
-        }
+    fn test_horizontal_mirror(
+        grid: &DenseMatrix<char>,
+        mirror_after: isize,
+        smudges: usize,
+    ) -> bool {
+        let (_, maxy) = grid.dimensions();
+        let range = std::cmp::min(mirror_after + 1, maxy - mirror_after);
+        let differences = (0..range)
+            .map(|i| {
+                let top = grid.row(mirror_after - i).unwrap();
+                let bottom = grid.row(mirror_after + 1 + i).unwrap();
+                Self::difference(top, bottom)
+            })
+            .sum::<usize>();
+        debug!(mirror_after, range, maxy, differences, "range");
         differences == smudges
     }
 
-    fn find_horizontal_mirror(grid: &Matrix<char>, smudges: usize) -> Vec<isize> {
+    fn find_horizontal_mirror(grid: &DenseMatrix<char>, smudges: usize) -> Vec<isize> {
         let (_, maxy) = grid.dimensions();
         let mut mirrors = Vec::new();
         for y in 0..maxy {
@@ -78,40 +47,31 @@ impl Solution {
         }
         mirrors
     }
+
+    /// A vertical mirror is a horizontal one in the transposed grid.
+    fn find_vertical_mirror(grid: &DenseMatrix<char>, smudges: usize) -> Vec<isize> {
+        Self::find_horizontal_mirror(&grid.transpose(), smudges)
+    }
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        let mut grid = Matrix::new();
-        let mut y = 0;
-        for line in reader.lines().map_while(Result::ok) {
-            if line.trim().is_empty() {
-                if !grid.is_empty() {
-                    solution.add_grid(grid);
-                }
-                grid = Matrix::new();
-                y = 0;
-            } else {
-                for (x, c) in line.chars().enumerate() {
-                    grid.set(x as isize, y, c);
-                }
-                y += 1;
-            }
-        }
-        if !grid.is_empty() {
+        for block in utils::parse::blocks(reader) {
+            let grid = DenseMatrix::from_lines(&block?)?;
             solution.add_grid(grid);
         }
         Ok(solution)
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let mut horizontal_mirrors = Vec::new();
         let mut vertical_mirrors = Vec::new();
         for grid in &self.grids {
@@ -137,7 +97,7 @@ impl utils::Solution for Solution {
         Ok(score)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let mut horizontal_mirrors = Vec::new();
         let mut vertical_mirrors = Vec::new();
         for grid in &self.grids {
@@ -1,8 +1,5 @@
 #![feature(ascii_char)]
-use std::{
-    collections::HashMap,
-    io::{BufRead, BufReader},
-};
+use std::{collections::HashMap, io::BufReader};
 
 use tracing::debug;
 
@@ -30,27 +27,29 @@ impl Solution {
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for line in reader.lines().map_while(Result::ok) {
+        for line in utils::parse::lines(reader) {
+            let line = line?.text;
             solution.set_sequence(&line);
         }
         Ok(solution)
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let result = self.sequence.split(',').map(Self::hash).sum();
         // Implement for problem
         Ok(result)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let mut boxes: HashMap<u64, Vec<(&str, &str)>> = HashMap::new();
         for step in self.sequence.split(',') {
             if let Some((label, length)) = step.split_once('=') {
@@ -1,21 +1,17 @@
-use std::io::{BufRead, BufReader};
+use std::{collections::HashSet, io::BufReader};
 use tracing::debug;
-use utils::Matrix;
+use utils::DenseMatrix;
 
 pub type ResultType = u64;
 
 #[derive(Debug, Default)]
 pub struct Solution {
-    contraption: Matrix<char>,
+    contraption: DenseMatrix<char>,
 }
 impl Solution {
-    fn set_cell(&mut self, x: isize, y: isize, c: char) {
-        self.contraption.set(x, y, c);
-    }
-
     fn count_energised(&self, start: (isize, isize, isize, isize)) -> ResultType {
         let (maxx, maxy) = self.contraption.dimensions();
-        let r = pathfinding::directed::dijkstra::dijkstra_all(&start, |(x, y, dx, dy)| {
+        let r = utils::graph::dijkstra_all(start, |(x, y, dx, dy)| {
             let x = *x;
             let y = *y;
             let dx = *dx;
@@ -62,38 +58,31 @@ impl Solution {
         });
 
         debug!(r = debug(&r), "dijkstra");
-        let mut g = r.iter().fold(Matrix::new(), |mut acc, (p, _)| {
-            acc.set(p.0, p.1, 1);
-            acc
-        });
-        g.set(start.0, start.1, 1);
-        g.len() as ResultType
+        // Count each energized tile once, whichever way beams cross it
+        let energized = r.costs.keys().map(|p| (p.0, p.1)).collect::<HashSet<_>>();
+        energized.len() as ResultType
     }
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
-        let mut solution = Self::default();
-        for (y, line) in reader.lines().map_while(Result::ok).enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                solution.set_cell(x as isize, y as isize, c);
-            }
-        }
-        Ok(solution)
+        let contraption = std::io::read_to_string(reader)?.parse()?;
+        Ok(Self { contraption })
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let result = self.count_energised((0, 0, 1, 0));
         Ok(result)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let (maxx, maxy) = self.contraption.dimensions();
         let mut max = 0;
         for y in 0..=maxy {
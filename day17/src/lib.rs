@@ -1,32 +1,25 @@
-use std::io::{BufRead, BufReader};
+use std::io::BufReader;
 use tracing::{debug, event_enabled, Level};
-use utils::Matrix;
+use utils::{graph::a_star, DenseMatrix};
 
 pub type ResultType = u64;
 
 #[derive(Debug, Default)]
 pub struct Solution {
-    island: Matrix<isize>,
+    island: DenseMatrix<isize>,
 }
-impl Solution {
-    fn set_block(&mut self, x: usize, y: usize, cost: u32) {
-        self.island.set(x as isize, y as isize, cost as isize);
-    }
-}
-
 #[allow(unused_variables, unused_mut)]
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
-        let mut solution = Self::default();
-        for (y, line) in reader.lines().map_while(Result::ok).enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                let cost = c.to_digit(10).unwrap();
-                solution.set_block(x, y, cost);
-            }
-        }
-        Ok(solution)
+        let lines = utils::parse::lines(reader).collect::<Result<Vec<_>, _>>()?;
+        let island = utils::parse::grid(
+            &lines,
+            |c| c.to_digit(10).map(|cost| cost as isize),
+            "a digit",
+        )?;
+        Ok(Self { island })
     }
 }
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
@@ -36,16 +29,6 @@ enum Direction {
     East,
     West,
 }
-impl Direction {
-    fn opposite(&self) -> Self {
-        match self {
-            Self::North => Self::South,
-            Self::South => Self::North,
-            Self::East => Self::West,
-            Self::West => Self::East,
-        }
-    }
-}
 type State = (isize, isize, Option<(Direction, usize)>);
 
 impl Solution {
@@ -185,20 +168,21 @@ impl Solution {
 }
 
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
-        let r = pathfinding::directed::astar::astar(
-            &(0, 0, None),
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
+        let r = a_star(
+            (0, 0, None),
             |s| self.successors_part1(s),
             |s| self.heuristic(s),
             |s| self.success(s),
         )
-        .unwrap();
+        .ok_or_else(|| anyhow::anyhow!("no route to the factory"))?;
         if event_enabled!(Level::DEBUG) {
-            debug!(r = debug(&r), "result");
-            let p = r.0.iter().fold(Matrix::new(), |mut path, v| {
+            debug!(cost = r.cost, expanded = r.expanded, "result");
+            let p = r.path.iter().fold(DenseMatrix::new(), |mut path, v| {
                 path.set(v.0, v.1, 1);
                 path
             });
@@ -212,20 +196,20 @@ impl utils::Solution for Solution {
             });
         }
         // Implement for problem
-        Ok(r.1 as ResultType)
+        Ok(r.cost as ResultType)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
-        let r = pathfinding::directed::astar::astar(
-            &(0, 0, None),
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
+        let r = a_star(
+            (0, 0, None),
             |s| self.successors_part2(s),
             |s| self.heuristic(s),
             |s| self.success(s),
         )
-        .unwrap();
+        .ok_or_else(|| anyhow::anyhow!("no route to the factory"))?;
         if event_enabled!(Level::DEBUG) {
-            debug!(r = debug(&r), "result");
-            let p = r.0.iter().fold(Matrix::new(), |mut path, v| {
+            debug!(cost = r.cost, expanded = r.expanded, "result");
+            let p = r.path.iter().fold(DenseMatrix::new(), |mut path, v| {
                 path.set(v.0, v.1, 1);
                 path
             });
@@ -238,6 +222,6 @@ impl utils::Solution for Solution {
                 .to_string()
             });
         }
-        Ok(r.1 as ResultType)
+        Ok(r.cost as ResultType)
     }
 }
@@ -1,5 +1,9 @@
-use std::io::{BufRead, BufReader};
+use std::io::BufReader;
 use tracing::debug;
+use utils::{
+    geometry::Polygon,
+    parse::{Line, ParseError},
+};
 
 pub type ResultType = i64;
 
@@ -12,27 +16,20 @@ impl Solution {
         self.trenches.push(trench);
     }
 
-    fn solve_directions(directions: &Vec<(Direction, ResultType)>) -> ResultType {
-        let mut pos: (ResultType, ResultType) = (0, 0);
-        let mut perimeter = 0;
-        let mut area = 0;
-
-        for (direction, length) in directions {
-            let (dx, dy) = match direction {
+    fn solve_directions(directions: &[(Direction, ResultType)]) -> ResultType {
+        let lagoon = Polygon::from_runs(directions.iter().map(|(direction, length)| {
+            let step = match direction {
                 Direction::Up => (0, -1),
                 Direction::Down => (0, 1),
                 Direction::Left => (-1, 0),
                 Direction::Right => (1, 0),
             };
-            pos.0 += dx * length;
-            pos.1 += dy * length;
-            perimeter += length;
-            area += pos.0 * (dy * length);
-        }
-
-        debug!(perimeter, area, "r");
+            (step, *length as i128)
+        }));
+        let (boundary, interior) = (lagoon.boundary_points(), lagoon.interior_points());
+        debug!(boundary, interior, "r");
 
-        area + perimeter / 2 + 1
+        (boundary + interior) as ResultType
     }
 }
 
@@ -49,55 +46,60 @@ struct Dig {
     length: usize,
     color: String,
 }
-impl From<String> for Dig {
-    fn from(value: String) -> Self {
-        let r =
-            regex::Regex::new(r"^(?<dir>.)\s+(?<length>\d+)\s+\((?<color>#[0-9a-f]+)\)$").unwrap();
-        let c = r.captures(&value).unwrap();
-        let direction = match c.name("dir").unwrap().as_str() {
+impl Dig {
+    /// Parse `<direction> <length> (#<color>)`.
+    fn parse(line: &Line) -> Result<Self, ParseError> {
+        let (direction, rest) = line.split_once(line.text.trim_start(), " ")?;
+        let direction = match direction {
             "U" => Direction::Up,
             "D" => Direction::Down,
             "L" => Direction::Left,
             "R" => Direction::Right,
-            _ => panic!(),
+            _ => return Err(line.error(direction, "a direction (U, D, L or R)")),
         };
-        let length = c.name("length").unwrap().as_str().parse().unwrap();
-        let color = c.name("color").unwrap().as_str().to_string();
+        let (length, color) = line.split_once(rest.trim_start(), " ")?;
+        let length = line.parse(length, "a length")?;
+        let color = color.trim();
+        match color.strip_prefix("(#").and_then(|c| c.strip_suffix(')')) {
+            Some(hex) if !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()) => {}
+            _ => return Err(line.error(color, "a color '(#<hex>)'")),
+        }
 
-        Dig {
+        Ok(Dig {
             direction,
             length,
-            color,
-        }
+            color: color[1..color.len() - 1].to_string(),
+        })
     }
 }
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for line in reader.lines().map_while(Result::ok) {
-            let dig = Dig::from(line);
+        for line in utils::parse::lines(reader) {
+            let dig = Dig::parse(&line?)?;
             solution.add_trench(dig);
         }
         Ok(solution)
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let directions = self
             .trenches
             .iter()
             .map(|trench| (trench.direction.clone(), trench.length as ResultType))
-            .collect();
+            .collect::<Vec<_>>();
         let r = Self::solve_directions(&directions);
         Ok(r as ResultType)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let directions = self
             .trenches
             .iter()
@@ -113,7 +115,7 @@ impl utils::Solution for Solution {
                 };
                 (direction, distance)
             })
-            .collect();
+            .collect::<Vec<_>>();
 
         let r = Self::solve_directions(&directions);
 
@@ -1,5 +1,10 @@
-use std::io::{BufRead, BufReader};
+use anyhow::Context;
+use std::io::BufReader;
 use tracing::debug;
+use utils::{
+    interval::{HyperRect, Interval},
+    parse::{Line, ParseError},
+};
 
 pub type ResultType = u64;
 
@@ -18,37 +23,38 @@ impl Solution {
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
         let mut mode = 0;
-        for line in reader.lines().map_while(Result::ok) {
-            // Implement for problem
-            if line.trim().is_empty() {
+        for line in utils::parse::lines(reader) {
+            let line = line?;
+            if line.text.trim().is_empty() {
                 mode += 1;
                 continue;
             }
             match mode {
                 0 => {
-                    let workflow = Workflow::from(line);
+                    let workflow = Workflow::parse(&line)?;
                     solution.add_workflow(workflow);
                 }
                 1 => {
-                    let part = Part::from(line);
+                    let part = Part::parse(&line)?;
                     solution.add_part(part);
                 }
-                _ => panic!(),
+                _ => return Err(line.error(&line.text, "the end of the input")),
             }
         }
         Ok(solution)
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let mut r = 0;
         for part in &self.parts {
             let mut cur_workflow_name = "in".to_string();
@@ -60,7 +66,7 @@ impl utils::Solution for Solution {
                     .workflows
                     .iter()
                     .find(|w| w.name == cur_workflow_name)
-                    .unwrap();
+                    .with_context(|| format!("unknown workflow '{cur_workflow_name}'"))?;
                 for condition in &workflow.conditions {
                     if condition.0.matches(part) {
                         cur_workflow_name = condition.1.to_owned();
@@ -70,189 +76,98 @@ impl utils::Solution for Solution {
             }
             debug!(part = debug(part), cur_workflow_name, "step");
             if cur_workflow_name == "A" {
-                r += part.x + part.m + part.a + part.s;
+                r += part.ratings.iter().sum::<ResultType>();
             }
         }
         // Implement for problem
         Ok(r)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let mut status = Vec::new();
         let mut accept = Vec::new();
-        status.push(("in", (1, 4000), (1, 4000), (1, 4000), (1, 4000)));
-        while let Some((name, mut x_range, mut m_range, mut a_range, mut s_range)) = status.pop() {
+        status.push(("in", HyperRect::new([Interval::inclusive(1, 4000); 4])));
+        while let Some((name, mut ratings)) = status.pop() {
             // Accept terminal
             if name == "A" {
-                accept.push((x_range, m_range, a_range, s_range));
+                accept.push(ratings);
                 continue;
             }
             // Reject terminal
             if name == "R" {
                 continue;
             }
-            let workflow = self.workflows.iter().find(|w| w.name == name).unwrap();
+            let workflow = self
+                .workflows
+                .iter()
+                .find(|w| w.name == name)
+                .with_context(|| format!("unknown workflow '{name}'"))?;
             debug!(
                 workflow = debug(workflow),
-                x_range = debug(x_range),
-                m_range = debug(m_range),
-                a_range = debug(a_range),
-                s_range = debug(s_range),
+                ratings = debug(ratings),
                 "compute out range(s)"
             );
             for (condition, output) in &workflow.conditions {
-                match condition {
+                // Split off the ratings which match, to carry on with the rest
+                let (matching, rest) = match condition {
                     Condition::None => {
-                        status.push((output, x_range, m_range, a_range, s_range));
-                    }
-                    Condition::Less(var, val) if var == "x" => {
-                        if x_range.0 < *val {
-                            let bottom = x_range.0;
-                            let top = std::cmp::min(val - 1, x_range.1);
-                            status.push((output, (bottom, top), m_range, a_range, s_range));
-                            if top != x_range.1 {
-                                x_range.0 = *val;
-                            } else {
-                                panic!();
-                            }
-                        }
-                    }
-                    Condition::Less(var, val) if var == "m" => {
-                        if m_range.0 < *val {
-                            let bottom = m_range.0;
-                            let top = std::cmp::min(val - 1, m_range.1);
-                            status.push((output, x_range, (bottom, top), a_range, s_range));
-                            if top != m_range.1 {
-                                m_range.0 = *val;
-                            } else {
-                                panic!();
-                            }
-                        }
-                    }
-                    Condition::Less(var, val) if var == "a" => {
-                        if a_range.0 < *val {
-                            let bottom = a_range.0;
-                            let top = std::cmp::min(val - 1, a_range.1);
-                            status.push((output, x_range, m_range, (bottom, top), s_range));
-                            if top != a_range.1 {
-                                a_range.0 = *val;
-                            } else {
-                                panic!();
-                            }
-                        }
-                    }
-                    Condition::Less(var, val) if var == "s" => {
-                        if s_range.0 < *val {
-                            let bottom = s_range.0;
-                            let top = std::cmp::min(val - 1, s_range.1);
-                            status.push((output, x_range, m_range, a_range, (bottom, top)));
-                            if top != s_range.1 {
-                                s_range.0 = *val;
-                            } else {
-                                panic!();
-                            }
-                        }
-                    }
-                    Condition::Greater(var, val) if var == "x" => {
-                        if x_range.1 > *val {
-                            let bottom = std::cmp::max(val + 1, x_range.0);
-                            let top = x_range.1;
-                            status.push((output, (bottom, top), m_range, a_range, s_range));
-                            if bottom != x_range.0 {
-                                x_range.1 = *val;
-                            } else {
-                                panic!();
-                            }
-                        }
-                    }
-                    Condition::Greater(var, val) if var == "m" => {
-                        if m_range.1 > *val {
-                            let bottom = std::cmp::max(val + 1, m_range.0);
-                            let top = m_range.1;
-                            status.push((output, x_range, (bottom, top), a_range, s_range));
-                            if bottom != m_range.0 {
-                                m_range.1 = *val;
-                            } else {
-                                panic!();
-                            }
-                        }
-                    }
-                    Condition::Greater(var, val) if var == "a" => {
-                        if a_range.1 > *val {
-                            let bottom = std::cmp::max(val + 1, a_range.0);
-                            let top = a_range.1;
-                            status.push((output, x_range, m_range, (bottom, top), s_range));
-                            if bottom != a_range.0 {
-                                a_range.1 = *val;
-                            } else {
-                                panic!();
-                            }
-                        }
+                        status.push((output, ratings));
+                        break;
                     }
-                    Condition::Greater(var, val) if var == "s" => {
-                        if s_range.1 > *val {
-                            let bottom = std::cmp::max(val + 1, s_range.0);
-                            let top = s_range.1;
-                            status.push((output, x_range, m_range, a_range, (bottom, top)));
-                            if bottom != s_range.0 {
-                                s_range.1 = *val;
-                            } else {
-                                panic!();
-                            }
-                        }
+                    Condition::Less(axis, val) => ratings.split_at(*axis, *val),
+                    Condition::Greater(axis, val) => {
+                        let (rest, matching) = ratings.split_at(*axis, val + 1);
+                        (matching, rest)
                     }
-
-                    _ => panic!(),
+                };
+                if !matching.is_empty() {
+                    status.push((output, matching));
+                }
+                if rest.is_empty() {
+                    break;
                 }
+                ratings = rest;
             }
         }
         debug!(accept = debug(&accept), count = accept.len(), "done?");
-        let r = accept
-            .into_iter()
-            .map(|(x_range, m_range, a_range, s_range)| {
-                (x_range.1 - x_range.0 + 1)
-                    * (m_range.1 - m_range.0 + 1)
-                    * (a_range.1 - a_range.0 + 1)
-                    * (s_range.1 - s_range.0 + 1)
-            })
-            .sum();
-        // Implement for problem
+        let r = accept.iter().map(HyperRect::volume).sum();
         Ok(r)
     }
 }
+/// The position of each category in a part's ratings.
+const CATEGORIES: [&str; 4] = ["x", "m", "a", "s"];
+
 #[derive(Debug)]
 enum Condition {
     None,
-    Less(String, ResultType),
-    Greater(String, ResultType),
+    /// The rating in the category at this index is less than the value
+    Less(usize, ResultType),
+    Greater(usize, ResultType),
 }
 impl Condition {
     fn matches(&self, part: &Part) -> bool {
         match self {
             Self::None => true,
-            Self::Less(var, value) if var == "x" => part.x < *value,
-            Self::Less(var, value) if var == "m" => part.m < *value,
-            Self::Less(var, value) if var == "a" => part.a < *value,
-            Self::Less(var, value) if var == "s" => part.s < *value,
-            Self::Greater(var, value) if var == "x" => part.x > *value,
-            Self::Greater(var, value) if var == "m" => part.m > *value,
-            Self::Greater(var, value) if var == "a" => part.a > *value,
-            Self::Greater(var, value) if var == "s" => part.s > *value,
-            _ => panic!("{self:?} vs {part:?}"),
+            Self::Less(axis, value) => part.ratings[*axis] < *value,
+            Self::Greater(axis, value) => part.ratings[*axis] > *value,
         }
     }
-}
-impl From<&str> for Condition {
-    fn from(value: &str) -> Self {
-        let r = regex::Regex::new(r"^(?<var>.)(?<condition>.)(?<value>\d+)$").unwrap();
-        let c = r.captures(value).unwrap();
-        let var = c.name("var").unwrap().as_str().to_string();
-        let value = c.name("value").unwrap().as_str().parse().unwrap();
-        match c.name("condition").unwrap().as_str() {
-            "<" => Self::Less(var, value),
-            ">" => Self::Greater(var, value),
-            _ => panic!("failed to parse: {value}"),
-        }
+
+    /// Parse `<category><operator><value>`, which is `condition` of `line`.
+    fn parse(line: &Line, condition: &str) -> Result<Self, ParseError> {
+        let Some(at) = condition.find(['<', '>']) else {
+            return Err(line.error(condition, "a comparison ('<' or '>')"));
+        };
+        let (category, value) = (&condition[..at], &condition[at + 1..]);
+        let axis = CATEGORIES
+            .iter()
+            .position(|c| *c == category)
+            .ok_or_else(|| line.error(category, "a category (x, m, a or s)"))?;
+        let value = line.parse(value, "a rating")?;
+        Ok(match &condition[at..at + 1] {
+            "<" => Self::Less(axis, value),
+            _ => Self::Greater(axis, value),
+        })
     }
 }
 #[derive(Debug)]
@@ -260,39 +175,58 @@ struct Workflow {
     name: String,
     conditions: Vec<(Condition, String)>,
 }
-impl From<String> for Workflow {
-    fn from(value: String) -> Self {
-        let (name, output) = value.split_once('{').unwrap();
-        let output = output.trim_end_matches('}');
+impl Workflow {
+    /// Parse `<name>{<condition>:<output>,...,<output>}`.
+    fn parse(line: &Line) -> Result<Self, ParseError> {
+        let (name, output) = line.split_once(line.text.trim(), "{")?;
+        let output = output
+            .strip_suffix('}')
+            .ok_or_else(|| line.error(&output[output.len()..], "'}'"))?;
         let conditions = output
             .split(',')
             .map(|s| match s.split_once(':') {
-                Some((a, b)) => (Condition::from(a), b.to_string()),
-                None => (Condition::None, s.to_string()),
+                Some((a, b)) => Ok((Condition::parse(line, a)?, b.to_string())),
+                None => Ok((Condition::None, s.to_string())),
             })
-            .collect();
-        Self {
+            .collect::<Result<Vec<_>, ParseError>>()?;
+        // Every part must go somewhere
+        if !matches!(conditions.last(), Some((Condition::None, _))) {
+            return Err(line.error(&output[output.len()..], "a final rule with no condition"));
+        }
+        Ok(Self {
             name: name.to_string(),
             conditions,
-        }
+        })
     }
 }
 #[derive(Debug)]
 struct Part {
-    x: ResultType,
-    m: ResultType,
-    a: ResultType,
-    s: ResultType,
+    /// In the order of [`CATEGORIES`]
+    ratings: [ResultType; 4],
 }
-impl From<String> for Part {
-    fn from(value: String) -> Self {
-        debug!(value, "part");
-        let r = regex::Regex::new(r"^\{x=(?<x>\d+),m=(?<m>\d+),a=(?<a>\d+),s=(?<s>\d+)\}").unwrap();
-        let c = r.captures(&value).unwrap();
-        let x = c.name("x").unwrap().as_str().parse().unwrap();
-        let m = c.name("m").unwrap().as_str().parse().unwrap();
-        let a = c.name("a").unwrap().as_str().parse().unwrap();
-        let s = c.name("s").unwrap().as_str().parse().unwrap();
-        Self { x, m, a, s }
+impl Part {
+    /// Parse `{x=<x>,m=<m>,a=<a>,s=<s>}`.
+    fn parse(line: &Line) -> Result<Self, ParseError> {
+        let text = line.text.trim();
+        let ratings = text
+            .strip_prefix('{')
+            .and_then(|r| r.strip_suffix('}'))
+            .ok_or_else(|| line.error(text, "'{x=..,m=..,a=..,s=..}'"))?;
+        let mut values = ratings.split(',');
+        let mut parsed = [0; 4];
+        for (category, rating) in CATEGORIES.iter().zip(parsed.iter_mut()) {
+            let value = values
+                .next()
+                .ok_or_else(|| line.error(&ratings[ratings.len()..], format!("'{category}='")))?;
+            let (name, value) = line.split_once(value, "=")?;
+            if name != *category {
+                return Err(line.error(name, format!("'{category}'")));
+            }
+            *rating = line.parse(value, "a rating")?;
+        }
+        if let Some(extra) = values.next() {
+            return Err(line.error(extra, "'}'"));
+        }
+        Ok(Self { ratings: parsed })
     }
 }
@@ -1,8 +0,0 @@
-use anyhow::Result;
-use day19::{ResultType, Solution};
-
-fn main() -> Result<()> {
-    utils::log_init();
-
-    utils::run::<Solution, ResultType>(&["sample"], &["full"])
-}
@@ -1,9 +1,7 @@
-use std::{
-    collections::HashMap,
-    io::{BufRead, BufReader},
-};
+use std::{collections::HashMap, io::BufReader, str::FromStr};
 
 use tracing::{debug, info};
+use utils::parse::{Line, ParseError};
 
 pub type ResultType = u64;
 
@@ -13,10 +11,11 @@ pub struct Solution {
 }
 
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let red = 12;
         let green = 13;
         let blue = 14;
@@ -47,7 +46,7 @@ impl utils::Solution for Solution {
         Ok(r)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let r = self
             .games
             .values()
@@ -80,15 +79,21 @@ impl Solution {
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for (id, line) in reader.lines().map_while(Result::ok).enumerate() {
-            if let Some((_game, sets)) = line.split_once(':') {
-                let sets = sets.split(';').map(Set::from).collect();
-                solution.add_game(id + 1, sets);
+        for line in utils::parse::lines(reader) {
+            let line = line?;
+            if line.text.trim().is_empty() {
+                continue;
             }
+            let (_game, sets) = line.split_once(&line.text, ":")?;
+            let sets = sets
+                .split(';')
+                .map(|set| Set::parse(&line, set))
+                .collect::<Result<_, _>>()?;
+            solution.add_game(line.number, sets);
         }
         Ok(solution)
     }
@@ -100,13 +105,15 @@ enum Color {
     Green,
     Blue,
 }
-impl From<&str> for Color {
-    fn from(color: &str) -> Self {
+impl FromStr for Color {
+    type Err = ();
+
+    fn from_str(color: &str) -> Result<Self, Self::Err> {
         match color {
-            "red" => Self::Red,
-            "green" => Self::Green,
-            "blue" => Self::Blue,
-            _ => panic!("illegal color {color}"),
+            "red" => Ok(Self::Red),
+            "green" => Ok(Self::Green),
+            "blue" => Ok(Self::Blue),
+            _ => Err(()),
         }
     }
 }
@@ -114,21 +121,20 @@ impl From<&str> for Color {
 struct Set {
     colors: Vec<(u64, Color)>,
 }
-impl From<&str> for Set {
-    fn from(value: &str) -> Self {
+impl Set {
+    fn parse(line: &Line, value: &str) -> Result<Self, ParseError> {
         debug!(value, "parse 1");
-        let r = regex::Regex::new(r"^(?<count>\d+)\s+(?<color>\w+)").unwrap();
         let colors = value
             .split(',')
             .map(|s| {
                 debug!(s, "parse 2");
-                let c = r.captures(s.trim()).unwrap();
-                let count = c.name("count").unwrap().as_str().parse().unwrap();
-                let color = c.name("color").unwrap().as_str().into();
-                (count, color)
+                let (count, color) = line.split_once(s.trim(), " ")?;
+                let count = line.parse(count, "a number of cubes")?;
+                let color = line.parse(color, "a color (red, green or blue)")?;
+                Ok::<_, ParseError>((count, color))
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
         debug!(value, colors = debug(&colors), "parse");
-        Self { colors }
+        Ok(Self { colors })
     }
 }
@@ -1,9 +1,10 @@
+use anyhow::Context;
 use std::{
     collections::{HashMap, HashSet, VecDeque},
-    io::{BufRead, BufReader},
+    io::BufReader,
 };
 use tracing::debug;
-use utils::math::lowest_common_multiple_many;
+use utils::math::first_common_step;
 
 pub type ResultType = u64;
 
@@ -13,6 +14,10 @@ enum Mode {
     FlipFlop,
     Conjunction,
 }
+/// Reads the `target` parameter, the module which part 2 waits to receive
+/// a low pulse, which is `rx` unless given. Neither sample has an `rx`, and
+/// the puzzle gives no part 2 answer for them, but the second can be run
+/// with `target: output`.
 #[derive(Debug, Default)]
 pub struct Solution {
     modules: Vec<(String, Mode, Vec<String>)>,
@@ -113,23 +118,24 @@ impl Solution {
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for line in reader.lines().map_while(Result::ok) {
-            // Implement for problem
-            let (from, to) = line.split_once(" -> ").unwrap();
+        for line in utils::parse::lines(reader) {
+            let line = line?;
+            let (from, to) = line.split_once(&line.text, " -> ")?;
             solution.add(from, to);
         }
         Ok(solution)
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let mut memory = self.setup_memory();
 
         let mut num_low = 0;
@@ -145,21 +151,25 @@ impl utils::Solution for Solution {
         Ok(num_high * num_low)
     }
 
-    fn answer_part2(&self, is_full: bool) -> Self::Result {
-        if !is_full {
-            return Ok(0);
-        }
+    fn answer_part2(&self, context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
+        let target = context.param("target", "rx".to_string())?;
 
         let mut memory = self.setup_memory();
         // broadcaster has 4x outputs, rx is conjunction of 1 (zh), which has 4x inputs -> separable graphs?
+        let (feeder, _, _) = self
+            .modules
+            .iter()
+            .find(|(_, mode, targets)| mode == &Mode::Conjunction && targets.contains(&target))
+            .with_context(|| format!("no conjunction feeds '{target}'"))?;
         let ends = memory
-            .get("zh")
+            .get(feeder)
             .unwrap()
             .keys()
             .cloned()
             .collect::<HashSet<_>>();
 
-        let mut first_seen = HashMap::new();
+        // Each end fires after `offset` presses and then every `period`
+        let mut seen: HashMap<_, Vec<i128>> = HashMap::new();
         let mut state = HashMap::new();
 
         let mut i = 0;
@@ -167,20 +177,23 @@ impl utils::Solution for Solution {
             let (_, _, encountered_ends) = self.perform_step(&ends, &mut state, &mut memory);
             i += 1;
             for encountered_end in encountered_ends {
-                first_seen.entry(encountered_end).or_insert(i);
+                let presses = seen.entry(encountered_end).or_default();
+                if presses.len() < 2 {
+                    presses.push(i);
+                }
             }
-            if first_seen.len() == ends.len() {
+            if seen.len() == ends.len() && seen.values().all(|presses| presses.len() == 2) {
                 break;
             }
             debug!(state = debug(&state), i, ends = debug(&ends), "s");
         }
-        let r = lowest_common_multiple_many(&first_seen.values().cloned().collect::<Vec<_>>());
-        debug!(
-            first_seen = debug(first_seen),
-            ends = debug(ends),
-            r,
-            "done?"
-        );
+        let cycles = seen
+            .values()
+            .map(|presses| (presses[0], presses[1] - presses[0]))
+            .collect::<Vec<_>>();
+        let r = first_common_step(&cycles).context("ends never all fire together")?;
+        debug!(seen = debug(seen), ends = debug(ends), r, "done?");
+        let r = r.try_into()?;
         Ok(r)
     }
 }
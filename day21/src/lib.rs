@@ -1,157 +1,104 @@
-use std::{
-    collections::HashSet,
-    io::{BufRead, BufReader},
-};
+use anyhow::Context;
+use std::io::BufReader;
 use tracing::debug;
-use utils::Matrix;
+use utils::{
+    graph::{at_exact_depth, bfs_distances, reachable_at_depth},
+    math::finite_differences,
+    DenseMatrix,
+};
 
 pub type ResultType = i64;
 
+/// Reads the `part1_steps` and `part2_steps` parameters, the number of
+/// steps taken in each part, which are 64 and 26501365 unless given. The
+/// puzzle gives 16 for the sample with `part1_steps: 6`, and for part 2
+/// 6536 with `part2_steps: 100` or 668697 with 1000.
 #[derive(Debug, Default)]
 pub struct Solution {
-    plots: Matrix<char>,
-}
-impl Solution {
-    fn set_plot(&mut self, x: usize, y: usize, c: char) {
-        self.plots.set(x as isize, y as isize, c);
-    }
+    plots: DenseMatrix<char>,
 }
-
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
-        let mut solution = Self::default();
-        for (y, line) in reader.lines().map_while(Result::ok).enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                solution.set_plot(x, y, c);
-            }
-            // Implement for problem
-        }
-        Ok(solution)
+        let lines = utils::parse::lines(reader).collect::<Result<Vec<_>, _>>()?;
+        let plots = utils::parse::grid(
+            &lines,
+            |c| matches!(c, '.' | '#' | 'S').then_some(c),
+            "a plot (., # or S)",
+        )?;
+        Ok(Self { plots })
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
+        let steps: usize = context.param("part1_steps", 64)?;
         // Find start position
-        let ((x, y), _) = self.plots.sparse_iter().find(|(_, c)| *c == &'S').unwrap();
+        let ((x, y), _) = self.plots.iter().find(|(_, c)| **c == 'S').unwrap();
 
-        let mut positions = HashSet::new();
-        positions.insert((*x, *y));
-        for i in 0..64 {
-            let mut next_positions = HashSet::new();
-            debug!(
-                positions = debug(&positions),
-                next_positions = debug(&next_positions),
-                i,
-                "step start"
-            );
-            for (x, y) in positions.iter() {
-                for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
-                    match self.plots.get(x + dx, y + dy).unwrap_or(&'#') {
-                        '.' | 'S' => {
-                            next_positions.insert((x + dx, y + dy));
-                        }
-                        _ => {}
-                    }
-                }
-            }
-            debug!(
-                positions = debug(positions),
-                next_positions = debug(&next_positions),
-                i,
-                "step end"
-            );
-            positions = next_positions;
-        }
+        let positions = reachable_at_depth(
+            (x, y),
+            |(x, y)| {
+                [(0, 1), (1, 0), (0, -1), (-1, 0)]
+                    .into_iter()
+                    .map(|(dx, dy)| (x + dx, y + dy))
+                    .filter(|(x, y)| matches!(self.plots.get(*x, *y), Some('.' | 'S')))
+                    .collect()
+            },
+            steps,
+        );
         debug!(plots = positions.len(), "done?");
         // Implement for problem
         Ok(positions.len() as ResultType)
     }
 
-    fn answer_part2(&self, is_full: bool) -> Self::Result {
-        if !is_full {
-            return Ok(0);
-        }
+    fn answer_part2(&self, context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
+        let steps: isize = context.param("part2_steps", 26501365)?;
         let (max_x, max_y) = self.plots.dimensions();
         debug!(max_x, max_y, "dimensions");
-        let ((sx, sy), _) = self.plots.sparse_iter().find(|(_, c)| *c == &'S').unwrap();
+        let ((sx, sy), _) = self.plots.iter().find(|(_, c)| **c == 'S').unwrap();
 
-        let mut positions = HashSet::new();
-        positions.insert((*sx, *sy));
-        // let mut totals = Vec::new();
-        // let mut deltas = Vec::new();
-        let mut stats = Vec::new();
-        let mut stats_idx = Vec::new();
+        // The garden repeats infinitely in every direction
+        let neighbours = |(x, y): &(isize, isize)| {
+            [(0, 1), (1, 0), (0, -1), (-1, 0)]
+                .into_iter()
+                .map(|(dx, dy)| (x + dx, y + dy))
+                .filter(|(x, y)| {
+                    let x = x.rem_euclid(max_x + 1);
+                    let y = y.rem_euclid(max_y + 1);
+                    matches!(self.plots.get(x, y), Some('.' | 'S'))
+                })
+                .collect()
+        };
+        if steps <= 1000 {
+            let positions = reachable_at_depth((sx, sy), neighbours, steps as usize);
+            return Ok(positions.len() as ResultType);
+        }
 
-        let mut i = 0;
         let double_loop_size = (1 + max_x) * 2;
-        loop {
-            i += 1;
-            let mut tmp2 = HashSet::new();
-            for (x, y) in positions.iter() {
-                for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
-                    let mut newx = x + dx;
-                    let mut newy = y + dy;
-
-                    while newx < 0 {
-                        newx += max_x + 1;
-                    }
-                    while newx > max_x {
-                        newx -= max_x + 1;
-                    }
-                    while newy < 0 {
-                        newy += max_y + 1;
-                    }
-                    while newy > max_y {
-                        newy -= max_y + 1;
-                    }
-
-                    match self.plots.get(newx, newy).unwrap() {
-                        '.' | 'S' => {
-                            tmp2.insert((x + dx, y + dy));
-                        }
-                        _ => {}
-                    }
-                }
-            }
-            if i % double_loop_size == *sx {
-                stats.push(tmp2.len());
-                stats_idx.push(i);
-                debug!(stats = debug(&stats), i, "s");
-            }
-
-            positions = tmp2;
-            if i > 1000 {
-                break;
-            }
-        }
-        let delta = stats.iter().fold((Vec::new(), 0), |(mut acc, last), v| {
-            acc.push(v - last);
-            (acc, *v)
-        });
-        let deltadelta = delta.0.iter().fold((Vec::new(), 0), |(mut acc, last), v| {
-            acc.push(v - last);
-            (acc, *v)
-        });
-        let loops_required = (26501365 - *sx) / double_loop_size;
-        let loops_remaining = loops_required - (stats_idx.len() - 1) as isize;
-        let mut current = *stats.last().unwrap();
-        let mut delta = *delta.0.last().unwrap();
-        let last_delta_delta = *deltadelta.0.last().unwrap();
-        for _ in 1..=loops_remaining {
-            delta += last_delta_delta;
-            current += delta;
-        }
-        // TOO low: 351,594,054, 90,192,836,242
-        // Wrong:
-        // 90193727915
-        // 584205647727006
-        // Right: 584211423220706
-        let answer = current;
+        let stats_idx = (1..=1000)
+            .filter(|i| i % double_loop_size == sx)
+            .collect::<Vec<_>>();
+        let distances = bfs_distances((sx, sy), neighbours, stats_idx.last().map(|i| *i as usize));
+        let stats = stats_idx
+            .iter()
+            .map(|i| at_exact_depth(&distances, *i as usize).count())
+            .collect::<Vec<_>>();
+        debug!(stats = debug(&stats), stats_idx = debug(&stats_idx), "s");
+        // The reachable count grows as a polynomial in the number of whole
+        // double-garden loops walked
+        let samples = stats.iter().map(|v| *v as i128).collect::<Vec<_>>();
+        let differences =
+            finite_differences(&samples).context("reachable plots do not grow polynomially")?;
+        debug!(degree = differences.degree(), "differences");
+        let loops_required = (steps - stats_idx[0]) / double_loop_size;
+        let answer = differences
+            .at(loops_required as i128)
+            .context("too many plots")?;
         Ok(answer as ResultType)
     }
 }
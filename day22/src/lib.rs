@@ -1,7 +1,8 @@
-use itertools::Itertools;
-use std::{
-    collections::{HashMap, HashSet},
-    io::{BufRead, BufReader},
+use std::{collections::HashMap, io::BufReader};
+use utils::{
+    graph::DominatorTree,
+    parse::{Line, ParseError},
+    Cuboid, Point3,
 };
 
 pub type ResultType = u64;
@@ -9,174 +10,124 @@ pub type ResultType = u64;
 #[derive(Debug, Default)]
 pub struct Solution {
     bricks: Vec<Brick>,
-    occupancy: HashMap<(ResultType, ResultType, ResultType), usize>,
+    /// Which bricks would fall if another were taken away, with the ground
+    /// as node 0 and then each brick in turn.
+    dominators: DominatorTree,
 }
 impl Solution {
     fn add_brick(&mut self, brick: Brick) {
         self.bricks.push(brick);
     }
 
-    fn get_falling(
-        falling: &mut HashSet<usize>,
-        above: &HashMap<usize, HashSet<usize>>,
-        below: &HashMap<usize, HashSet<usize>>,
-        brick_id: usize,
-    ) {
-        if !falling.insert(brick_id) {
-            return;
-        }
-        if let Some(parents) = above.get(&brick_id) {
-            for &parent in parents {
-                if below[&parent].iter().all(|x| falling.contains(x)) {
-                    Self::get_falling(falling, above, below, parent);
-                }
-            }
-        }
-    }
-    fn gen_above_below(
-        &self,
-    ) -> (
-        std::collections::HashMap<usize, std::collections::HashSet<usize>>,
-        std::collections::HashMap<usize, std::collections::HashSet<usize>>,
-    ) {
-        let mut above = HashMap::<_, HashSet<_>>::new();
-        let mut below = HashMap::<_, HashSet<_>>::new();
-        for (i, brick) in self.bricks.iter().enumerate() {
-            let x1 = brick.start.x;
-            let y1 = brick.start.y;
-            let z1 = brick.start.z;
-            let x2 = brick.end.x;
-            let y2 = brick.end.y;
-            for (x, y) in (x1..=x2).cartesian_product(y1..=y2) {
-                if let Some(&j) = self.occupancy.get(&(x, y, z1 - 1)) {
-                    above.entry(j).or_default().insert(i);
-                    below.entry(i).or_default().insert(j);
-                }
+    /// Drop the bricks, lowest first, each straight down onto the heights of
+    /// those already settled, returning the bricks each one comes to rest on.
+    fn settle(&mut self) -> Vec<Vec<usize>> {
+        self.bricks.sort_by_key(|brick| brick.min().z());
+        // The top of the pile at each (x, y), and the brick there
+        let mut heights = HashMap::<(ResultType, ResultType), (ResultType, usize)>::new();
+        let mut below = Vec::with_capacity(self.bricks.len());
+        for (id, brick) in self.bricks.iter_mut().enumerate() {
+            let footprint = footprint(brick);
+            let tops = footprint
+                .iter()
+                .filter_map(|column| heights.get(column))
+                .copied()
+                .collect::<Vec<_>>();
+            let top = tops.iter().map(|(z, _)| *z).max().unwrap_or(0);
+            let mut resting_on = tops
+                .into_iter()
+                .filter(|(z, _)| *z == top)
+                .map(|(_, id)| id)
+                .collect::<Vec<_>>();
+            resting_on.sort();
+            resting_on.dedup();
+
+            let drop = Point3::new(0, 0, brick.min().z() - (top + 1));
+            *brick = Cuboid::new(brick.min() - drop, brick.max() - drop);
+            for column in footprint {
+                heights.insert(column, (brick.max().z(), id));
             }
+            below.push(resting_on);
         }
-        (above, below)
+        below
     }
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for line in reader.lines().map_while(Result::ok) {
-            // Implement for problem
-            let brick = Brick::from(line.as_str());
+        for line in utils::parse::lines(reader) {
+            let brick = parse_brick(&line?)?;
             solution.add_brick(brick);
         }
         Ok(solution)
     }
 }
-#[derive(Debug, Clone)]
-struct Point3 {
-    x: ResultType,
-    y: ResultType,
-    z: ResultType,
-}
-impl From<&str> for Point3 {
-    fn from(value: &str) -> Self {
-        let r = regex::Regex::new(r"^(?<x>\d+),(?<y>\d+),(?<z>\d+)$").unwrap();
-        let c = r.captures(value).unwrap();
-        let x = c.name("x").unwrap().as_str().parse().unwrap();
-        let y = c.name("y").unwrap().as_str().parse().unwrap();
-        let z = c.name("z").unwrap().as_str().parse().unwrap();
-        Self { x, y, z }
-    }
+type Brick = Cuboid<ResultType>;
+
+/// Parse `<x>,<y>,<z>`, which is `value` of `line`.
+fn parse_point(line: &Line, value: &str) -> Result<Point3<ResultType>, ParseError> {
+    let coordinates: [ResultType; 3] = line.separated_array(value, ",", "a coordinate")?;
+    Ok(Point3::from(coordinates))
 }
-#[derive(Debug, Clone)]
-struct Brick {
-    start: Point3,
-    end: Point3,
+
+/// Parse `<x>,<y>,<z>~<x>,<y>,<z>`.
+fn parse_brick(line: &Line) -> Result<Brick, ParseError> {
+    let (lhs, rhs) = line.split_once(&line.text, "~")?;
+    Ok(Cuboid::new(
+        parse_point(line, lhs)?,
+        parse_point(line, rhs)?,
+    ))
 }
-impl From<&str> for Brick {
-    fn from(value: &str) -> Self {
-        let (lhs, rhs) = value.split_once('~').unwrap();
-        let start = Point3::from(lhs);
-        let end = Point3::from(rhs);
-        Self { start, end }
-    }
+
+/// The (x, y) columns which `brick` covers.
+fn footprint(brick: &Brick) -> Vec<(ResultType, ResultType)> {
+    let (min, max) = (brick.min(), brick.max());
+    Cuboid::new(min, Point3::new(max.x(), max.y(), min.z()))
+        .points()
+        .map(|point| (point.x(), point.y()))
+        .collect()
 }
+
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {
-        for (i, brick) in self.bricks.iter().enumerate() {
-            for x in brick.start.x..=brick.end.x {
-                for y in brick.start.y..=brick.end.y {
-                    for z in brick.start.z..=brick.end.z {
-                        self.occupancy.insert((x, y, z), i);
-                    }
-                }
-            }
-        }
-        let mut done = false;
-        while !done {
-            done = true;
-            for (i, brick) in self.bricks.iter_mut().enumerate() {
-                loop {
-                    let min_x = brick.start.x;
-                    let min_y = brick.start.y;
-                    let min_z = brick.start.z;
-                    let max_x = brick.end.x;
-                    let max_y = brick.end.y;
-                    let max_z = brick.end.z;
-                    if min_z == 1 {
-                        break;
-                    }
-                    if (min_x..=max_x)
-                        .cartesian_product(min_y..=max_y)
-                        .any(|(x, y)| self.occupancy.contains_key(&(x, y, min_z - 1)))
-                    {
-                        break;
-                    }
-                    // Brick drops
-                    *brick = Brick {
-                        start: Point3 {
-                            x: min_x,
-                            y: min_y,
-                            z: min_z - 1,
-                        },
-                        end: Point3 {
-                            x: max_x,
-                            y: max_y,
-                            z: max_z - 1,
-                        },
-                    };
-                    for (x, y) in (min_x..=max_x).cartesian_product(min_y..=max_y) {
-                        self.occupancy.remove(&(x, y, max_z));
-                        self.occupancy.insert((x, y, min_z - 1), i);
-                    }
-                    done = false;
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {
+        let below = self.settle();
+        // A brick falls once everything holding it up from the ground has gone
+        let predecessors = std::iter::once(Vec::new())
+            .chain(below.iter().map(|resting_on| {
+                if resting_on.is_empty() {
+                    vec![0]
+                } else {
+                    resting_on.iter().map(|id| id + 1).collect()
                 }
-            }
-        }
+            }))
+            .collect::<Vec<_>>();
+        self.dominators = DominatorTree::from_dag(&predecessors);
     }
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
-        let (above, below) = self.gen_above_below();
-        let mut answer = 0 as ResultType;
-        for id in 0..self.bricks.len() {
-            let mut falling = HashSet::new();
-            Self::get_falling(&mut falling, &above, &below, id);
-            answer += if falling.len() == 1 { 1 } else { 0 };
-        }
-        // Implement for problem
-        // Too high: 416
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
+        // Bricks which nothing else depends on alone
+        let answer = self
+            .dominators
+            .dominated_counts()
+            .iter()
+            .skip(1)
+            .filter(|count| **count == 0)
+            .count();
         Ok(answer as ResultType)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
-        let (above, below) = self.gen_above_below();
-        let mut answer = 0 as ResultType;
-        for id in 0..self.bricks.len() {
-            let mut falling = HashSet::new();
-            Self::get_falling(&mut falling, &above, &below, id);
-            answer += falling.len() as ResultType - 1;
-        }
-        // 70727?
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
+        let answer = self
+            .dominators
+            .dominated_counts()
+            .iter()
+            .skip(1)
+            .sum::<usize>();
         Ok(answer as ResultType)
     }
 }
@@ -1,12 +1,19 @@
-use std::io::{BufRead, BufReader};
+use anyhow::anyhow;
+use std::io::BufReader;
 use tracing::debug;
-use z3::{
-    ast::{self, Ast},
-    Config, Context, SatResult, Solver,
+use utils::{
+    geometry::{Intersection, Ray},
+    linalg::solve_integer,
+    math::Rational,
+    parse::{Line, ParseError},
+    Point3,
 };
 
-pub type ResultType = f64;
+pub type ResultType = i128;
 
+/// Reads the `min` and `max` parameters, the bounds along x and y of the
+/// test area in part 1, which are 200000000000000 and 400000000000000
+/// unless given. The sample uses `min: 7` and `max: 27`.
 #[derive(Debug, Default)]
 pub struct Solution {
     hailstones: Vec<HailStone>,
@@ -18,185 +25,130 @@ impl Solution {
 }
 #[derive(Debug, Clone)]
 struct HailStone {
-    px: ResultType,
-    py: ResultType,
-    pz: ResultType,
-    vx: ResultType,
-    vy: ResultType,
-    vz: ResultType,
+    position: Point3<ResultType>,
+    velocity: Point3<ResultType>,
 }
-impl From<String> for HailStone {
-    fn from(value: String) -> Self {
-        debug!(value);
-        let r = regex::Regex::new(r"^(?<px>[-0-9]+),\s(?<py>[-0-9]+),\s+(?<pz>[-0-9]+)\s+@\s+(?<vx>[-0-9]+),\s+(?<vy>[-0-9]+),\s+(?<vz>[-0-9]+)$").unwrap();
-        let c = r.captures(&value).unwrap();
-        let px = c.name("px").unwrap().as_str().parse().unwrap();
-        let py = c.name("py").unwrap().as_str().parse().unwrap();
-        let pz = c.name("pz").unwrap().as_str().parse().unwrap();
-        let vx = c.name("vx").unwrap().as_str().parse().unwrap();
-        let vy = c.name("vy").unwrap().as_str().parse().unwrap();
-        let vz = c.name("vz").unwrap().as_str().parse().unwrap();
-        Self {
-            px,
-            py,
-            pz,
-            vx,
-            vy,
-            vz,
-        }
+impl HailStone {
+    /// The path in the x-y plane, ignoring z.
+    fn path_xy(&self) -> Ray<2> {
+        Ray::new(
+            [self.position.x(), self.position.y()],
+            [self.velocity.x(), self.velocity.y()],
+        )
+    }
+}
+impl HailStone {
+    /// Parse `<px>, <py>, <pz> @ <vx>, <vy>, <vz>`.
+    fn parse(line: &Line) -> Result<Self, ParseError> {
+        let (position, velocity) = line.split_once(&line.text, "@")?;
+        let position: [ResultType; 3] = line.separated_array(position, ",", "a position")?;
+        let velocity: [ResultType; 3] = line.separated_array(velocity, ",", "a velocity")?;
+        Ok(Self {
+            position: Point3::from(position),
+            velocity: Point3::from(velocity),
+        })
     }
 }
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for line in reader.lines().map_while(Result::ok) {
-            // Implement for problem
-            let hailstone = HailStone::from(line);
+        for line in utils::parse::lines(reader) {
+            let hailstone = HailStone::parse(&line?)?;
             solution.add_hailstone(hailstone);
         }
         Ok(solution)
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = u64;
+    type Part2 = i64;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, is_full: bool) -> Self::Result {
-        let (min, max) = if is_full {
-            (200000000000000.0, 400000000000000.0)
-        } else {
-            (7.0, 27.0)
-        };
-        let mut tracks = Vec::new();
-        for hailstone in &self.hailstones {
-            let tx0 = (min - hailstone.px) / hailstone.vx;
-            let tx1 = (max - hailstone.px) / hailstone.vx;
-            let ty0 = (min - hailstone.py) / hailstone.vy;
-            let ty1 = (max - hailstone.py) / hailstone.vy;
-            let t = tx0.max(tx1).max(ty0).max(ty1);
-            debug!(hailstone = debug(hailstone), tx0, tx1, ty0, ty1, t);
-            let endx = hailstone.px + hailstone.vx * t;
-            let endy = hailstone.py + hailstone.vy * t;
-            tracks.push(((hailstone.px, hailstone.py), (endx, endy), t));
-        }
-        let mut count = 0.0;
-        for (id1, (start1, end1, _)) in tracks.iter().enumerate() {
-            for (id2, (start2, end2, _)) in tracks.iter().enumerate() {
-                if id1 >= id2 {
-                    continue;
-                }
-                let x1 = start1.0;
-                let x2 = end1.0;
-                let x3 = start2.0;
-                let x4 = end2.0;
-                let y1 = start1.1;
-                let y2 = end1.1;
-                let y3 = start2.1;
-                let y4 = end2.1;
-                let denom = (x1 - x2) * (y3 - y4) - (y1 - y2) * (x3 - x4);
-                if denom != 0.0 {
-                    let nom_t = (x1 - x3) * (y3 - y4) - (y1 - y3) * (x3 - x4);
-                    let nom_u = (x1 - x3) * (y1 - y2) - (y1 - y3) * (x1 - x2);
-                    let nom_x = (x1 * y2 - y1 * x2) * (x3 - x4) - (x1 - x2) * (x3 * y4 - y3 * x4);
-                    let nom_y = (x1 * y2 - y1 * x2) * (y3 - y4) - (y1 - y2) * (x3 * y4 - y3 * x4);
-                    let int_x = nom_x / denom;
-                    let int_y = nom_y / denom;
-                    //         count += 1.0;
-                    let int_t = nom_t / denom;
-                    let int_u = nom_u / denom;
-                    if (min..=max).contains(&int_x)
-                        && (min..=max).contains(&int_y)
-                        && (0.0..=1.0).contains(&int_t)
-                        && (0.0..=1.0).contains(&int_u)
-                    {
+    fn answer_part1(&self, context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
+        let min = Rational::integer(context.param("min", 200000000000000)?);
+        let max = Rational::integer(context.param("max", 400000000000000)?);
+        let paths = self
+            .hailstones
+            .iter()
+            .map(HailStone::path_xy)
+            .collect::<Vec<_>>();
+        let mut count = 0;
+        for (id1, path1) in paths.iter().enumerate() {
+            for (id2, path2) in paths.iter().enumerate().skip(id1 + 1) {
+                match path1.ray_intersection(path2) {
+                    Intersection::Crossing { point, t, u } => {
+                        let inside = point.iter().all(|v| (min..=max).contains(v));
+                        debug!(
+                            id1,
+                            id2,
+                            point = debug(point),
+                            t = debug(t),
+                            u = debug(u),
+                            inside,
+                            "paths cross"
+                        );
+                        if inside {
+                            count += 1;
+                        }
+                    }
+                    Intersection::Missed { t, u, .. } => {
                         debug!(
-                            int_x,
-                            int_y,
-                            int_t,
-                            int_u,
-                            start1 = debug(start1),
-                            end1 = debug(end1),
-                            start2 = debug(start2),
-                            end2 = debug(end2)
+                            id1,
+                            id2,
+                            t = debug(t),
+                            u = debug(u),
+                            "paths crossed in the past"
                         );
-                        count += 1.0;
                     }
-                } else {
-                    // Assume don't intersect for now
+                    Intersection::Parallel | Intersection::Skew => {
+                        debug!(id1, id2, "paths never cross");
+                    }
+                    Intersection::Collinear { overlapping } => {
+                        if overlapping {
+                            return Err(anyhow!("hailstones {id1} and {id2} share a path"));
+                        }
+                        debug!(id1, id2, "paths on the same line never meet");
+                    }
                 }
             }
         }
-        // Implement for problem
         Ok(count)
     }
 
-    fn answer_part2(&self, is_full: bool) -> Self::Result {
-        let (min, max) = if is_full {
-            (200000000000000.0, 400000000000000.0)
-        } else {
-            (7.0, 27.0)
-        };
-        let mut tracks = Vec::new();
-        for hailstone in &self.hailstones {
-            let tx0 = (min - hailstone.px) / hailstone.vx;
-            let tx1 = (max - hailstone.px) / hailstone.vx;
-            let ty0 = (min - hailstone.py) / hailstone.vy;
-            let ty1 = (max - hailstone.py) / hailstone.vy;
-            let t = tx0.max(tx1).max(ty0).max(ty1);
-            debug!(hailstone = debug(hailstone), tx0, tx1, ty0, ty1, t);
-            let endx = hailstone.px + hailstone.vx * t;
-            let endy = hailstone.py + hailstone.vy * t;
-            tracks.push(((hailstone.px, hailstone.py), (endx, endy), t));
-        }
-        // Implement for problem
-        let ctx = Context::new(&Config::default());
-        let solver = Solver::new(&ctx);
-        let zero = ast::Int::from_i64(&ctx, 0);
-
-        let rock_x = ast::Int::new_const(&ctx, "rock_x");
-        let rock_y = ast::Int::new_const(&ctx, "rock_y");
-        let rock_z = ast::Int::new_const(&ctx, "rock_z");
-        let rock_vx = ast::Int::new_const(&ctx, "rock_vx");
-        let rock_vy = ast::Int::new_const(&ctx, "rock_vy");
-        let rock_vz = ast::Int::new_const(&ctx, "rock_vz");
-
-        for (i, hailstone) in self.hailstones.iter().enumerate() {
-            if i > 4 {
-                continue;
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
+        // The rock, at P moving at V, meets each hailstone at p moving at v, so
+        // (P - p) × (V - v) = 0. Expanded, the P × V term is the same for every
+        // hailstone, so taking pairs apart leaves equations linear in P and V:
+        //   P × (v' - v) + (p' - p) × V = p' × v' - p × v
+        let (first, others) = self
+            .hailstones
+            .split_first()
+            .ok_or_else(|| anyhow!("no hailstones"))?;
+        let mut coefficients = Vec::new();
+        let mut constants = Vec::new();
+        // Three pairs give 9 equations for the 6 unknowns, in case two are degenerate
+        for other in others.iter().take(3) {
+            let dp: [ResultType; 3] = (other.position - first.position).into();
+            let dv: [ResultType; 3] = (other.velocity - first.velocity).into();
+            let constant =
+                other.position.cross(&other.velocity) - first.position.cross(&first.velocity);
+            for (axis, constant) in <[ResultType; 3]>::from(constant).into_iter().enumerate() {
+                let (i, j) = ((axis + 1) % 3, (axis + 2) % 3);
+                let mut row = vec![0; 6];
+                // (P × dv)[axis] = P[i] dv[j] - P[j] dv[i]
+                row[i] = dv[j];
+                row[j] = -dv[i];
+                // (dp × V)[axis] = dp[i] V[j] - dp[j] V[i]
+                row[3 + j] = dp[i];
+                row[3 + i] = -dp[j];
+                coefficients.push(row);
+                constants.push(constant);
             }
-            let t_name = format!("t{i}");
-            let t = ast::Int::new_const(&ctx, t_name);
-            solver.assert(&t.ge(&zero));
-            let x = ast::Int::from_i64(&ctx, hailstone.px as i64);
-            let vx = ast::Int::from_i64(&ctx, hailstone.vx as i64);
-            let px = &x + &vx * &t;
-            let rxt = &rock_x + &rock_vx * &t;
-            solver.assert(&px._eq(&rxt));
-            let y = ast::Int::from_i64(&ctx, hailstone.py as i64);
-            let vy = ast::Int::from_i64(&ctx, hailstone.vy as i64);
-            let py = &y + &vy * &t;
-            let ryt = &rock_y + &rock_vy * &t;
-            solver.assert(&py._eq(&ryt));
-            let z = ast::Int::from_i64(&ctx, hailstone.pz as i64);
-            let vz = ast::Int::from_i64(&ctx, hailstone.vz as i64);
-            let pz = &z + &vz * &t;
-            let rzt = &rock_z + &rock_vz * &t;
-            solver.assert(&pz._eq(&rzt));
         }
-
-        let r = match solver.check() {
-            SatResult::Sat => {
-                let model = solver.get_model().unwrap();
-                debug!(model = debug(&model));
-                let x = model.get_const_interp(&rock_x).unwrap().as_i64().unwrap();
-                let y = model.get_const_interp(&rock_y).unwrap().as_i64().unwrap();
-                let z = model.get_const_interp(&rock_z).unwrap().as_i64().unwrap();
-                x + y + z
-            }
-            _ => todo!(),
-        };
-        Ok(r as ResultType)
+        let rock = solve_integer(&coefficients, &constants)?;
+        debug!(rock = debug(&rock));
+        Ok((rock[0] + rock[1] + rock[2]).try_into()?)
     }
 }
@@ -1,23 +1,24 @@
 use std::{
-    collections::HashMap,
-    io::{BufRead, BufReader},
+    collections::{HashMap, HashSet},
+    io::BufReader,
 };
 
 use tracing::debug;
-use utils::Matrix;
+use utils::{Grid, Point};
 
 pub type ResultType = u64;
 
 #[derive(Debug, Default)]
 pub struct Solution {
-    data: Matrix<char>,
+    data: Grid<char, isize>,
     found_numbers: Vec<(isize, isize, isize, ResultType)>,
 }
 
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {
-        let (maxx, maxy) = self.data.dimensions();
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {
+        let (maxx, maxy) = (*self.data.max_x(), *self.data.max_y());
 
         self.found_numbers = Vec::new();
         for y in 0..=maxy {
@@ -27,7 +28,7 @@ impl utils::Solution for Solution {
             let mut end_number = 0;
 
             for x in 0..=maxx + 1 {
-                let c = self.data.get(x, y).unwrap_or(&'.');
+                let c = self.data.get(&Point::new(x, y)).unwrap_or(&'.');
                 match c {
                     '0'..='9' if in_number => {
                         cur_number *= 10;
@@ -58,7 +59,7 @@ impl utils::Solution for Solution {
         debug!(found_numbers = debug(&self.found_numbers), "numbers");
     }
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         fn is_symbol(c: char) -> bool {
             !(c.is_numeric() || c == '.')
         }
@@ -67,32 +68,27 @@ impl utils::Solution for Solution {
             .found_numbers
             .iter()
             .filter(|(sx, ex, y, _)| {
-                for y in y - 1..=y + 1 {
-                    for x in sx - 1..=ex + 1 {
-                        let c = self.data.get(x, y).unwrap_or(&'.');
-                        if is_symbol(*c) {
-                            return true;
-                        }
-                    }
-                }
-                false
+                (*sx..=*ex).any(|x| {
+                    self.data
+                        .neighbours(&Point::new(x, *y))
+                        .any(|(_, c)| is_symbol(*c))
+                })
             })
             .map(|(_, _, _, v)| *v as ResultType)
             .sum();
         Ok(r)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let mut gears = HashMap::new();
         for (sx, ex, y, v) in self.found_numbers.iter() {
-            for y in y - 1..=y + 1 {
-                for x in sx - 1..=ex + 1 {
-                    let c = *self.data.get(x, y).unwrap_or(&'.');
-                    // Gear
-                    if c == '*' {
-                        gears.entry((x, y)).or_insert(Vec::new()).push(v);
-                    }
-                }
+            let adjacent_gears = (*sx..=*ex)
+                .flat_map(|x| self.data.neighbours(&Point::new(x, *y)))
+                .filter(|(_, c)| **c == '*')
+                .map(|(point, _)| point)
+                .collect::<HashSet<_>>();
+            for gear in adjacent_gears {
+                gears.entry(gear).or_insert(Vec::new()).push(v);
             }
         }
 
@@ -112,23 +108,15 @@ impl utils::Solution for Solution {
     }
 }
 
-impl Solution {
-    fn set(&mut self, x: usize, y: usize, c: char) {
-        self.data.set(x as isize, y as isize, c);
-    }
-}
-
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
-        let mut solution = Self::default();
-        for (y, line) in reader.lines().map_while(Result::ok).enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                solution.set(x, y, c);
-            }
-        }
-        Ok(solution)
+        let data = std::io::read_to_string(reader)?.parse()?;
+        Ok(Self {
+            data,
+            ..Self::default()
+        })
     }
 }
 #[cfg(test)]
@@ -153,7 +141,10 @@ mod test {
         .join("\n");
         let r = BufReader::new(input.as_bytes());
         let mut s = crate::Solution::try_from(r).unwrap();
-        s.analyse(false);
-        assert_eq!(4361 as ResultType, s.answer_part1(false).unwrap());
+        s.analyse(&utils::RunContext::new());
+        assert_eq!(
+            4361 as ResultType,
+            s.answer_part1(&utils::RunContext::new()).unwrap()
+        );
     }
 }
@@ -1,6 +1,6 @@
 use std::{
     collections::{HashMap, HashSet},
-    io::{BufRead, BufReader},
+    io::BufReader,
 };
 
 use tracing::debug;
@@ -13,10 +13,11 @@ pub struct Solution {
 }
 
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let mut total = 0;
         for (winning, have) in &self.cards {
             let mut score = 0;
@@ -34,7 +35,7 @@ impl utils::Solution for Solution {
         Ok(total)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let mut count = HashMap::new();
         for (id, _) in self.cards.iter().enumerate() {
             count.insert(id, 1);
@@ -69,25 +70,16 @@ impl Solution {
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for line in reader.lines().map_while(Result::ok) {
-            let (_, data) = line.split_once(':').unwrap();
-            let (lhs, rhs) = data.split_once('|').unwrap();
-            let winning = lhs
-                .trim()
-                .split(' ')
-                .filter(|v| !v.is_empty())
-                .map(|v| v.trim().parse().unwrap())
-                .collect();
-            let have = rhs
-                .trim()
-                .split(' ')
-                .filter(|v| !v.is_empty())
-                .map(|v| v.trim().parse().unwrap())
-                .collect();
+        for line in utils::parse::lines(reader) {
+            let line = line?;
+            let (_, data) = line.key_value()?;
+            let (lhs, rhs) = line.split_once(data, "|")?;
+            let winning = line.numbers(lhs)?.into_iter().collect();
+            let have = line.numbers(rhs)?;
             solution.add_cards(winning, have);
         }
         Ok(solution)
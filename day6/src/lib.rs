@@ -1,4 +1,4 @@
-use std::io::{BufRead, BufReader};
+use std::io::BufReader;
 
 use tracing::debug;
 
@@ -13,10 +13,11 @@ pub struct Solution {
 }
 
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let result = self
             .times
             .iter()
@@ -29,7 +30,7 @@ impl utils::Solution for Solution {
         Ok(result)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let num_wins = Self::calculate_wins(self.distance, self.time);
         Ok(num_wins)
     }
@@ -63,34 +64,29 @@ impl Solution {
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for (id, line) in reader.lines().map_while(Result::ok).enumerate() {
-            let (_, values) = line.split_once(':').unwrap();
-            let int_values = values
-                .trim()
-                .split(' ')
-                .filter(|v| !v.is_empty())
-                .map(|v| v.parse::<u64>().unwrap())
-                .collect::<Vec<_>>();
+        for line in utils::parse::lines(reader) {
+            let line = line?;
+            let (key, values) = line.key_value()?;
+            let int_values = line.numbers(values)?;
             let fat_value = values
-                .chars()
-                .filter(|c| !c.is_whitespace())
+                .split_whitespace()
                 .collect::<String>()
                 .parse()
-                .unwrap();
-            match id {
-                0 => {
+                .map_err(|_| line.error(values, "a number"))?;
+            match key {
+                "Time" => {
                     solution.set_times(int_values);
                     solution.set_time(fat_value);
                 }
-                1 => {
+                "Distance" => {
                     solution.set_distances(int_values);
                     solution.set_distance(fat_value);
                 }
-                _ => panic!(),
+                _ => return Err(line.error(key, "'Time' or 'Distance'")),
             }
         }
         Ok(solution)
@@ -1,9 +1,7 @@
-use std::{
-    collections::HashMap,
-    io::{BufRead, BufReader},
-};
+use std::{collections::HashMap, io::BufReader};
 
 use tracing::debug;
+use utils::parse::{Line, ParseError};
 
 pub type ResultType = u64;
 
@@ -13,10 +11,11 @@ pub struct Solution {
 }
 
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let mut hands = self.hands.clone();
         let strength = "23456789TJQKA";
         hands.sort_by(|a, b| {
@@ -26,8 +25,8 @@ impl utils::Solution for Solution {
                 std::cmp::Ordering::Equal => a
                     .hand
                     .chars()
-                    .map(|c| (strength.find(c).unwrap()))
-                    .cmp(b.hand.chars().map(|c| (strength.find(c).unwrap()))),
+                    .map(|c| strength.find(c).unwrap())
+                    .cmp(b.hand.chars().map(|c| strength.find(c).unwrap())),
                 c => c,
             };
             debug!(
@@ -49,7 +48,7 @@ impl utils::Solution for Solution {
         Ok(result)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         let strength = "J23456789TQKA";
         let mut hands = self
             .hands
@@ -67,8 +66,8 @@ impl utils::Solution for Solution {
                 std::cmp::Ordering::Equal => {
                     a.1.hand
                         .chars()
-                        .map(|c| (strength.find(c).unwrap()))
-                        .cmp(b.1.hand.chars().map(|c| (strength.find(c).unwrap())))
+                        .map(|c| strength.find(c).unwrap())
+                        .cmp(b.1.hand.chars().map(|c| strength.find(c).unwrap()))
                 }
                 c => c,
             };
@@ -99,8 +98,8 @@ impl Solution {
         let o = match a_type.partial_cmp(&b_type).unwrap() {
             std::cmp::Ordering::Equal => a_hand
                 .chars()
-                .map(|c| (strength.find(c).unwrap()))
-                .cmp(b_hand.chars().map(|c| (strength.find(c).unwrap()))),
+                .map(|c| strength.find(c).unwrap())
+                .cmp(b_hand.chars().map(|c| strength.find(c).unwrap())),
             c => c,
         };
         debug!(
@@ -125,7 +124,7 @@ impl Solution {
                 *acc.entry(v).or_default() += 1;
                 acc
             });
-        if c.get(&'J').is_none() {
+        if !c.contains_key(&'J') {
             return hand.to_string();
         }
         for replacement in strength.chars() {
@@ -171,12 +170,12 @@ impl Solution {
 }
 
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for line in reader.lines().map_while(Result::ok) {
-            let hand: Hand = line.into();
+        for line in utils::parse::lines(reader) {
+            let hand = Hand::parse(&line?)?;
             solution.add_hand(hand);
         }
         Ok(solution)
@@ -188,13 +187,18 @@ struct Hand {
     hand: String,
     bid: u64,
 }
-impl From<String> for Hand {
-    fn from(value: String) -> Self {
-        let r = regex::Regex::new(r"^(?<hand>\w+)\s+(?<bid>\d+)$").unwrap();
-        let c = r.captures(&value).unwrap();
-        let hand = c.name("hand").unwrap().as_str().to_string();
-        let bid = c.name("bid").unwrap().as_str().parse().unwrap();
-        Self { hand, bid }
+impl Hand {
+    /// Parse `<five cards> <bid>`.
+    fn parse(line: &Line) -> Result<Self, ParseError> {
+        let (hand, bid) = line.split_once(line.text.trim(), " ")?;
+        if hand.chars().count() != 5 || !hand.chars().all(|c| "23456789TJQKA".contains(c)) {
+            return Err(line.error(hand, "five cards (2-9, T, J, Q, K or A)"));
+        }
+        let bid = line.parse(bid, "a bid")?;
+        Ok(Self {
+            hand: hand.to_string(),
+            bid,
+        })
     }
 }
 #[derive(Clone, Debug, PartialEq)]
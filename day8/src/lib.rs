@@ -1,11 +1,11 @@
-use std::{
-    collections::HashMap,
-    io::{BufRead, BufReader},
-};
+use std::{collections::HashMap, io::BufReader};
 
 use anyhow::Context;
 use tracing::debug;
-use utils::math::lowest_common_multiple_many;
+use utils::{
+    math::first_common_step,
+    parse::{Line, ParseError},
+};
 
 pub type ResultType = u64;
 
@@ -24,16 +24,20 @@ impl Solution {
     }
 }
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for (id, line) in reader.lines().map_while(Result::ok).enumerate() {
-            let line = line.trim();
-            if id == 0 {
-                solution.set_instructions(line);
-            } else if !line.is_empty() {
-                let adjacency = Adjacency::from(line);
+        for line in utils::parse::lines(reader) {
+            let line = line?;
+            if line.number == 1 {
+                let instructions = line.text.trim();
+                if instructions.is_empty() || !instructions.chars().all(|c| c == 'L' || c == 'R') {
+                    return Err(line.error(instructions, "instructions (L or R)"));
+                }
+                solution.set_instructions(instructions);
+            } else if !line.text.trim().is_empty() {
+                let adjacency = Adjacency::parse(&line)?;
                 solution.add_adjacency(adjacency);
             }
         }
@@ -47,14 +51,31 @@ struct Adjacency {
     left: String,
     right: String,
 }
-impl From<&str> for Adjacency {
-    fn from(value: &str) -> Self {
-        let r = regex::Regex::new(r"(?<name>\w+)\s+=\s+\((?<left>\w+), (?<right>\w+)\)$").unwrap();
-        let c = r.captures(value).unwrap();
-        let name = c.name("name").unwrap().as_str().to_string();
-        let left = c.name("left").unwrap().as_str().to_string();
-        let right = c.name("right").unwrap().as_str().to_string();
-        Self { name, left, right }
+impl Adjacency {
+    /// Parse `<name> = (<left>, <right>)`.
+    fn parse(line: &Line) -> Result<Self, ParseError> {
+        let node = |text: &str| {
+            let text = text.trim();
+            if !text.is_empty() && text.chars().all(char::is_alphanumeric) {
+                Ok(text.to_string())
+            } else {
+                Err(line.error(text, "a node name"))
+            }
+        };
+        let (name, targets) = line.split_once(&line.text, "=")?;
+        let targets = targets.trim();
+        let Some(inner) = targets
+            .strip_prefix('(')
+            .and_then(|targets| targets.strip_suffix(')'))
+        else {
+            return Err(line.error(targets, "'(<left>, <right>)'"));
+        };
+        let (left, right) = line.split_once(inner, ",")?;
+        Ok(Self {
+            name: node(name)?,
+            left: node(left)?,
+            right: node(right)?,
+        })
     }
 }
 
@@ -82,10 +103,11 @@ impl Solution {
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         let mut node = "AAA";
         let mut steps = 0;
         loop {
@@ -109,16 +131,45 @@ impl utils::Solution for Solution {
         Ok(steps as u64)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
-        let d = self
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
+        // Each ghost reaches its first Z after `offset` steps, then loops back
+        // round to it every `period` steps
+        let cycles = self
             .network
             .keys()
             .filter(|k| k.ends_with('A'))
-            .map(|node| self.next_z(node, 0).1)
+            .map(|node| {
+                let (z, offset) = self.next_z(node, 0);
+                let (_, period) = self.next_z(&z, offset);
+                (offset as i128, period as i128)
+            })
             .collect::<Vec<_>>();
-        let result = lowest_common_multiple_many(&d[..]);
-        Ok(result)
+        debug!(cycles = debug(&cycles), "cycles");
+        let result = first_common_step(&cycles).context("ghosts never all reach Z together")?;
+        Ok(result.try_into()?)
     }
 }
 #[cfg(test)]
-mod test {}
+mod test {
+    use super::*;
+    use utils::Solution as _;
+
+    #[test]
+    fn ghosts() {
+        let input = [
+            "LR",
+            "",
+            "11A = (11B, XXX)",
+            "11B = (XXX, 11Z)",
+            "11Z = (11B, XXX)",
+            "22A = (22B, XXX)",
+            "22B = (22C, 22C)",
+            "22C = (22Z, 22Z)",
+            "22Z = (22B, 22B)",
+            "XXX = (XXX, XXX)",
+        ]
+        .join("\n");
+        let s = crate::Solution::try_from(BufReader::new(input.as_bytes())).unwrap();
+        assert_eq!(6, s.answer_part2(&utils::RunContext::new()).unwrap());
+    }
+}
@@ -1,9 +1,8 @@
-use std::{
-    collections::VecDeque,
-    io::{BufRead, BufReader},
-};
+use std::io::BufReader;
 
+use anyhow::Context;
 use tracing::debug;
+use utils::math::{lowest_degree_differences, Differences};
 
 pub type ResultType = i64;
 
@@ -15,49 +14,22 @@ impl Solution {
     fn add_history(&mut self, value: Vec<ResultType>) {
         self.history.push(value);
     }
-    fn next_element(row: &Vec<ResultType>) -> ResultType {
-        let mut diff = VecDeque::new();
-        diff.push_back(row.to_owned());
-        loop {
-            if let Some(last) = diff.back() {
-                if last.iter().all(|v| *v == 0 as ResultType) {
-                    break;
-                }
-                let next = last.iter().fold((Vec::new(), None), |(mut acc, last), v| {
-                    if let Some(l) = last {
-                        acc.push(v - l);
-                    }
-                    (acc, Some(v))
-                });
-                debug!(last = debug(&last), next = debug(&next), "next");
-                diff.push_back(next.0);
-            } else {
-                panic!();
-            }
-        }
-        let mut prev: Option<Vec<ResultType>> = None;
-        while let Some(n) = diff.pop_back() {
-            let mut current = n;
-            match prev {
-                None => current.push(0),
-                Some(p) => {
-                    let new = p.last().unwrap() + current.last().unwrap();
-                    current.push(new);
-                }
-            }
-            prev = Some(current);
-        }
-        debug!(prev = debug(&prev), "prev");
-        *prev.unwrap().last().unwrap()
+    fn differences(row: &[ResultType]) -> anyhow::Result<Differences> {
+        let samples = row.iter().map(|v| *v as i128).collect::<Vec<_>>();
+        let differences =
+            lowest_degree_differences(&samples).context("a history needs at least one value")?;
+        debug!(rows = debug(differences.rows()), "differences");
+        Ok(differences)
     }
 }
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for line in reader.lines().map_while(Result::ok) {
-            let row = line.split(' ').map(|v| v.parse().unwrap()).collect();
+        for line in utils::parse::lines(reader) {
+            let line = line?;
+            let row = line.numbers(&line.text)?;
             solution.add_history(row);
         }
         Ok(solution)
@@ -65,27 +37,70 @@ impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
 }
 
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
-        let result = self.history.iter().map(Self::next_element).sum();
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
+        let mut result = 0;
+        for row in &self.history {
+            let next = Self::differences(row)?.next().context("overflow")?;
+            result += ResultType::try_from(next)?;
+        }
         Ok(result)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
-        let result = self
-            .history
-            .iter()
-            .map(|row| {
-                let mut row = row.clone();
-                row.reverse();
-                Self::next_element(&row)
-            })
-            .sum();
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
+        let mut result = 0;
+        for row in &self.history {
+            let previous = Self::differences(row)?.previous().context("overflow")?;
+            result += ResultType::try_from(previous)?;
+        }
         Ok(result)
     }
 }
 
 #[cfg(test)]
-mod test {}
+mod test {
+    use super::*;
+    use std::io::BufReader;
+
+    use tracing_test::traced_test;
+    use utils::Solution;
+
+    fn solution(input: &[&str]) -> crate::Solution {
+        let input = input.join("\n");
+        let r = BufReader::new(input.as_bytes());
+        crate::Solution::try_from(r).unwrap()
+    }
+
+    #[test]
+    #[traced_test]
+    fn sample() {
+        let s = solution(&["0 3 6 9 12 15", "1 3 6 10 15 21", "10 13 16 21 30 45"]);
+        assert_eq!(
+            114 as ResultType,
+            s.answer_part1(&utils::RunContext::new()).unwrap()
+        );
+        assert_eq!(
+            2 as ResultType,
+            s.answer_part2(&utils::RunContext::new()).unwrap()
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn short_histories() {
+        // Too short for the differences to become constant
+        let s = solution(&["1 3", "1 2 4", "5"]);
+        // 5, 7 and 5 next, and -1, 1 and 5 before
+        assert_eq!(
+            17 as ResultType,
+            s.answer_part1(&utils::RunContext::new()).unwrap()
+        );
+        assert_eq!(
+            5 as ResultType,
+            s.answer_part2(&utils::RunContext::new()).unwrap()
+        );
+    }
+}
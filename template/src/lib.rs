@@ -1,5 +1,4 @@
-use std::io::{BufRead, BufReader};
-use tracing::{debug, event_enabled, info, Level};
+use std::io::BufReader;
 
 pub type ResultType = u64;
 
@@ -9,26 +8,28 @@ impl Solution {}
 
 #[allow(unused_variables, unused_mut)]
 impl<T: std::io::Read> TryFrom<BufReader<T>> for Solution {
-    type Error = std::io::Error;
+    type Error = utils::parse::ParseError;
 
     fn try_from(reader: BufReader<T>) -> Result<Self, Self::Error> {
         let mut solution = Self::default();
-        for (id, line) in reader.lines().map_while(Result::ok).enumerate() {
+        for line in utils::parse::lines(reader) {
+            let line = line?;
             // Implement for problem
         }
         Ok(solution)
     }
 }
 impl utils::Solution for Solution {
-    type Result = anyhow::Result<ResultType>;
-    fn analyse(&mut self, _is_full: bool) {}
+    type Part1 = ResultType;
+    type Part2 = ResultType;
+    fn analyse(&mut self, _context: &utils::RunContext) {}
 
-    fn answer_part1(&self, _is_full: bool) -> Self::Result {
+    fn answer_part1(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part1> {
         // Implement for problem
         Ok(0)
     }
 
-    fn answer_part2(&self, _is_full: bool) -> Self::Result {
+    fn answer_part2(&self, _context: &utils::RunContext) -> anyhow::Result<Self::Part2> {
         // Implement for problem
         Ok(0)
     }
@@ -48,6 +49,9 @@ mod test {
         let input = "replace for problem";
         let r = BufReader::new(input.as_bytes());
         let s = crate::Solution::try_from(r).unwrap();
-        assert_eq!(0 as ResultType, s.answer_part1(false).unwrap());
+        assert_eq!(
+            0 as ResultType,
+            s.answer_part1(&utils::RunContext::new()).unwrap()
+        );
     }
 }
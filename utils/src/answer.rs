@@ -0,0 +1,87 @@
+use std::fmt::Display;
+
+/// A puzzle answer, as displayed, compared and serialized by the runner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    /// Multi-line text, such as letters drawn on a grid.
+    Lines(Vec<String>),
+}
+impl Answer {
+    /// Whether this answer matches the `expected` text, ignoring leading and
+    /// trailing whitespace on the answer and on each of its lines.
+    pub fn matches(&self, expected: &str) -> bool {
+        fn normalise(text: &str) -> String {
+            text.trim()
+                .lines()
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        normalise(&self.to_string()) == normalise(expected)
+    }
+}
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Text(text) => write!(f, "{text}"),
+            Self::Lines(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+macro_rules! integer_answer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Self::Integer(value as i128)
+                }
+            }
+        )*
+    };
+}
+integer_answer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, i128, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_owned())
+    }
+}
+impl From<Vec<String>> for Answer {
+    fn from(value: Vec<String>) -> Self {
+        Self::Lines(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!("-12", Answer::from(-12i64).to_string());
+        assert_eq!("18446744073709551615", Answer::from(u64::MAX).to_string());
+        assert_eq!("abc", Answer::from("abc").to_string());
+        assert_eq!(
+            "#..\n.#.",
+            Answer::from(vec!["#..".to_string(), ".#.".to_string()]).to_string()
+        );
+    }
+
+    #[test]
+    fn matches() {
+        assert!(Answer::from(142u64).matches(" 142 "));
+        assert!(!Answer::from(142u64).matches("143"));
+        let lines = Answer::from(vec!["#.. ".to_string(), ".#.".to_string()]);
+        assert!(lines.matches("#..\n  .#.\n"));
+        assert!(!lines.matches("#.."));
+    }
+}
@@ -0,0 +1,77 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use anyhow::{Context, Result};
+
+use crate::sidecar;
+
+/// Named puzzle parameters for one input file, read from `<input>.params`.
+///
+/// Puzzles whose sample and full inputs ask different questions (a number of
+/// steps, a search area, ...) read those values from here instead of
+/// special-casing the input they are run against.
+#[derive(Debug, Clone, Default)]
+pub struct RunContext {
+    params: HashMap<String, String>,
+}
+impl RunContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the parameters for the input file `filename`.
+    pub fn load(filename: &str) -> Result<Self> {
+        let params = sidecar::load(&format!("{filename}.params"))?
+            .into_iter()
+            .map(|entry| (entry.name, entry.value))
+            .collect();
+        Ok(Self { params })
+    }
+
+    /// Set the parameter `name` to `value`.
+    pub fn with(mut self, name: &str, value: impl Display) -> Self {
+        self.params.insert(name.to_owned(), value.to_string());
+        self
+    }
+
+    /// The parameter `name`, if it was given.
+    pub fn get<T>(&self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        self.params
+            .get(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .with_context(|| format!("invalid value '{value}' for parameter '{name}'"))
+            })
+            .transpose()
+    }
+
+    /// The parameter `name`, or `default` if it was not given.
+    pub fn param<T>(&self, name: &str, default: T) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        Ok(self.get(name)?.unwrap_or(default))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn params() {
+        let context = RunContext::new().with("steps", 6).with("target", "output");
+        assert_eq!(6, context.param("steps", 64).unwrap());
+        assert_eq!(64, context.param("other", 64).unwrap());
+        assert_eq!(
+            Some("output".to_string()),
+            context.get::<String>("target").unwrap()
+        );
+        assert!(context.get::<u64>("target").is_err());
+    }
+}
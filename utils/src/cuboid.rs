@@ -0,0 +1,92 @@
+use std::{iter::Step, ops::Add};
+
+use crate::Point3;
+
+/// An axis-aligned box of points in three dimensions, including both of
+/// its corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid<T> {
+    min: Point3<T>,
+    max: Point3<T>,
+}
+
+impl<T: Copy + Ord> Cuboid<T> {
+    /// The box with opposite corners `a` and `b`, in either order.
+    pub fn new(a: Point3<T>, b: Point3<T>) -> Self {
+        Self {
+            min: a.zip_with(&b, Ord::min),
+            max: a.zip_with(&b, Ord::max),
+        }
+    }
+
+    /// The corner with the lowest coordinates.
+    pub fn min(&self) -> Point3<T> {
+        self.min
+    }
+
+    /// The corner with the highest coordinates.
+    pub fn max(&self) -> Point3<T> {
+        self.max
+    }
+
+    pub fn contains(&self, point: &Point3<T>) -> bool {
+        (self.min.x()..=self.max.x()).contains(&point.x())
+            && (self.min.y()..=self.max.y()).contains(&point.y())
+            && (self.min.z()..=self.max.z()).contains(&point.z())
+    }
+
+    /// The box of points in both, if there are any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = self.min.zip_with(&other.min, Ord::max);
+        let max = self.max.zip_with(&other.max, Ord::min);
+        (min.x() <= max.x() && min.y() <= max.y() && min.z() <= max.z())
+            .then_some(Self { min, max })
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+}
+
+impl<T: Copy + Add<Output = T>> Cuboid<T> {
+    /// The same box moved by `offset`.
+    pub fn translate(&self, offset: Point3<T>) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+}
+
+impl<T: Copy + Step> Cuboid<T> {
+    /// Every point in the box, in order of `x`, then `y`, then `z`.
+    pub fn points(&self) -> impl Iterator<Item = Point3<T>> + '_ {
+        (self.min.x()..=self.max.x()).flat_map(move |x| {
+            (self.min.y()..=self.max.y())
+                .flat_map(move |y| (self.min.z()..=self.max.z()).map(move |z| Point3::new(x, y, z)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn boxes() {
+        let a = Cuboid::new(Point3::new(2, 0, 5), Point3::new(0, 2, 3));
+        assert_eq!(Point3::new(0, 0, 3), a.min());
+        assert_eq!(Point3::new(2, 2, 5), a.max());
+        assert!(a.contains(&Point3::new(1, 2, 4)));
+        assert!(!a.contains(&Point3::new(1, 3, 4)));
+        assert_eq!(27, a.points().count());
+
+        let b = a.translate(Point3::new(2, 1, -2));
+        assert_eq!(
+            Some(Cuboid::new(Point3::new(2, 1, 3), Point3::new(2, 2, 3))),
+            a.intersection(&b)
+        );
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&b.translate(Point3::new(0, 0, -1))));
+    }
+}
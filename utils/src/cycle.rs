@@ -0,0 +1,199 @@
+//! Finding where a sequence of states `x0, f(x0), f(f(x0)), ...` starts to
+//! repeat, so that the state after a huge number of steps can be worked out
+//! without taking them all.
+use std::collections::HashMap;
+use std::hash::Hash;
+use tracing::debug;
+
+/// Where a sequence of states starts repeating: the state after step `n` is
+/// the same as after step `n + length` for every `n >= start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// The first step which is part of the cycle.
+    pub start: usize,
+    /// How many steps it takes to come back round.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as `step`.
+    pub fn equivalent_step(&self, step: usize) -> usize {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        }
+    }
+}
+
+/// Find the cycle using Floyd's tortoise and hare, which keeps only a
+/// couple of states at a time but takes `step` around three times as often
+/// as [`first_repeat`].
+pub fn floyd<S, F>(initial: S, step: F) -> Cycle
+where
+    S: PartialEq,
+    F: Fn(&S) -> S,
+{
+    // The hare runs at double speed until it laps the tortoise
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    // Distance from the start to the cycle equals that from the meeting point
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Find the cycle using Brent's algorithm, which keeps only a couple of
+/// states at a time and usually takes fewer steps than [`floyd`].
+pub fn brent<S, F>(initial: S, step: F) -> Cycle
+where
+    S: PartialEq + Clone,
+    F: Fn(&S) -> S,
+{
+    // Search successive powers of two for the cycle length
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Then walk two states `length` apart until they meet at the start
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Find the cycle by remembering every state seen, returning it along with
+/// the states from `initial` up to just before the first repeat.
+pub fn first_repeat<S, F>(initial: S, mut step: F) -> (Cycle, Vec<S>)
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = initial;
+    loop {
+        if let Some(start) = seen.get(&state) {
+            let cycle = Cycle {
+                start: *start,
+                length: history.len() - start,
+            };
+            debug!(cycle = debug(&cycle), "first repeat");
+            return (cycle, history);
+        }
+        seen.insert(state.clone(), history.len());
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+}
+
+/// The state after `steps` steps from `initial`, found by running until
+/// either that many steps have been taken or the states start to repeat.
+pub fn state_at<S, F>(initial: S, mut step: F, steps: usize) -> S
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = initial;
+    for taken in 0..steps {
+        if let Some(start) = seen.get(&state) {
+            let cycle = Cycle {
+                start: *start,
+                length: taken - start,
+            };
+            debug!(cycle = debug(&cycle), steps, "extrapolating");
+            return history.swap_remove(cycle.equivalent_step(steps));
+        }
+        seen.insert(state.clone(), taken);
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+    state
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// 3 steps to reach a cycle of 5: 0, 1, 2, 3, 4, 5, 6, 7, 3, ...
+    fn step(n: &u32) -> u32 {
+        if *n == 7 {
+            3
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn detection() {
+        let expected = Cycle {
+            start: 3,
+            length: 5,
+        };
+        assert_eq!(expected, floyd(0, step));
+        assert_eq!(expected, brent(0, step));
+        let (cycle, history) = first_repeat(0, step);
+        assert_eq!(expected, cycle);
+        assert_eq!((0..8).collect::<Vec<_>>(), history);
+
+        let fixed = Cycle {
+            start: 0,
+            length: 1,
+        };
+        assert_eq!(fixed, floyd(7, |n| *n));
+        assert_eq!(fixed, brent(7, |n| *n));
+    }
+
+    #[test]
+    fn extrapolation() {
+        let cycle = Cycle {
+            start: 3,
+            length: 5,
+        };
+        assert_eq!(2, cycle.equivalent_step(2));
+        assert_eq!(4, cycle.equivalent_step(1_000_000_000 - 1));
+
+        assert_eq!(5, state_at(0, step, 5));
+        assert_eq!(4, state_at(0, step, 1_000_000_000 - 1));
+        assert_eq!(3, state_at(0, step, 1_000_000_003));
+    }
+}
@@ -0,0 +1,466 @@
+use std::cmp::{max, min};
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use crate::parse::{Line, ParseError};
+
+/// A rectangular grid stored contiguously in row-major order, with the same
+/// interface as the sparse [`Matrix`](crate::Matrix) it replaces.
+///
+/// Every cell within the bounds exists: cells which have not been set hold
+/// `T::default()`. Setting a cell outside the bounds grows the grid.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct DenseMatrix<T> {
+    data: Vec<T>,
+    width: usize,
+    height: usize,
+    min_x: isize,
+    min_y: isize,
+}
+
+impl<T> DenseMatrix<T>
+where
+    T: Default + Display + Clone,
+{
+    pub fn new() -> DenseMatrix<T> {
+        DenseMatrix {
+            data: Vec::new(),
+            width: 0,
+            height: 0,
+            min_x: 0,
+            min_y: 0,
+        }
+    }
+
+    /// A grid of `width` by `height` default cells, with `(0, 0)` at the top
+    /// left.
+    pub fn with_size(width: usize, height: usize) -> DenseMatrix<T> {
+        DenseMatrix {
+            data: vec![T::default(); width * height],
+            width,
+            height,
+            min_x: 0,
+            min_y: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn index_of(&self, x: isize, y: isize) -> Option<usize> {
+        let dx = usize::try_from(x - self.min_x).ok()?;
+        let dy = usize::try_from(y - self.min_y).ok()?;
+        (dx < self.width && dy < self.height).then_some(dy * self.width + dx)
+    }
+
+    /// The cell at `(x, y)`, or `None` if it is outside the bounds.
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.data[i])
+    }
+
+    pub fn get_mut(&mut self, x: isize, y: isize) -> Option<&mut T> {
+        self.index_of(x, y).map(|i| &mut self.data[i])
+    }
+
+    pub fn set(&mut self, x: isize, y: isize, value: T) {
+        if self.index_of(x, y).is_none() {
+            self.grow(x, y);
+        }
+        let i = self.index_of(x, y).unwrap();
+        self.data[i] = value;
+    }
+
+    /// Grow the bounds to include `(x, y)`, as well as the origin.
+    fn grow(&mut self, x: isize, y: isize) {
+        let (max_x, max_y) = self.dimensions();
+        let (min_x, max_x) = (min(self.min_x, x), max(max_x, x));
+        let (min_y, max_y) = (min(self.min_y, y), max(max_y, y));
+        let width = (max_x - min_x + 1) as usize;
+        let height = (max_y - min_y + 1) as usize;
+        let mut data = vec![T::default(); width * height];
+        for (dy, row) in self.data.chunks(self.width.max(1)).enumerate() {
+            let start = (self.min_y - min_y) as usize + dy;
+            let start = start * width + (self.min_x - min_x) as usize;
+            data[start..start + row.len()].clone_from_slice(row);
+        }
+        *self = Self {
+            data,
+            width,
+            height,
+            min_x,
+            min_y,
+        };
+    }
+
+    pub fn dimensions(&self) -> (isize, isize) {
+        (self.max_x(), self.max_y())
+    }
+
+    pub fn max_x(&self) -> isize {
+        self.min_x + max(self.width, 1) as isize - 1
+    }
+
+    pub fn min_x(&self) -> isize {
+        self.min_x
+    }
+
+    pub fn max_y(&self) -> isize {
+        self.min_y + max(self.height, 1) as isize - 1
+    }
+
+    pub fn min_y(&self) -> isize {
+        self.min_y
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Every cell with its coordinates, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        let width = self.width.max(1);
+        self.data.iter().enumerate().map(move |(i, value)| {
+            let x = self.min_x + (i % width) as isize;
+            let y = self.min_y + (i / width) as isize;
+            ((x, y), value)
+        })
+    }
+
+    /// The cells of row `y`, from left to right.
+    pub fn row(&self, y: isize) -> Option<&[T]> {
+        let start = self.index_of(self.min_x, y)?;
+        Some(&self.data[start..start + self.width])
+    }
+
+    /// The cells of column `x`, from top to bottom.
+    pub fn column(&self, x: isize) -> Option<impl Iterator<Item = &T>> {
+        let start = self.index_of(x, self.min_y)?;
+        Some(self.data[start..].iter().step_by(self.width))
+    }
+
+    /// Every row, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.data.chunks(self.width.max(1))
+    }
+
+    /// The `width` by `height` view with its top left cell at `(x, y)`, if
+    /// it lies entirely within the bounds.
+    pub fn window(&self, x: isize, y: isize, width: usize, height: usize) -> Option<View<'_, T>> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+        self.index_of(x, y)?;
+        self.index_of(x + width as isize - 1, y + height as isize - 1)?;
+        Some(View {
+            matrix: self,
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// Every `width` by `height` view, in row-major order of their top left
+    /// cells.
+    pub fn windows(&self, width: usize, height: usize) -> impl Iterator<Item = View<'_, T>> {
+        let xs = self.min_x..=self.max_x();
+        (self.min_y..=self.max_y())
+            .flat_map(move |y| xs.clone().map(move |x| (x, y)))
+            .filter_map(move |(x, y)| self.window(x, y, width, height))
+    }
+
+    /// Build a `width` by `height` grid, at the origin, whose cell `(x, y)` is
+    /// taken from this grid's cell `source(x, y)`, relative to its top left.
+    fn remap(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> usize) -> Self {
+        let mut data = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                data.push(self.data[source(x, y)].clone());
+            }
+        }
+        Self {
+            data,
+            width,
+            height,
+            min_x: 0,
+            min_y: 0,
+        }
+    }
+
+    /// Swap rows and columns. Like every transformation, the result has its
+    /// top left cell at the origin.
+    pub fn transpose(&self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.remap(h, w, |x, y| x * w + y)
+    }
+
+    /// Rotate a quarter turn clockwise: the left column becomes the top row.
+    pub fn rotate_90(&self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.remap(h, w, |x, y| (h - 1 - x) * w + y)
+    }
+
+    pub fn rotate_180(&self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.remap(w, h, |x, y| (h - 1 - y) * w + (w - 1 - x))
+    }
+
+    /// Rotate a quarter turn anticlockwise: the top row becomes the left
+    /// column.
+    pub fn rotate_270(&self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.remap(h, w, |x, y| x * w + (w - 1 - y))
+    }
+
+    /// Mirror left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.remap(w, h, |x, y| y * w + (w - 1 - x))
+    }
+
+    /// Mirror top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.remap(w, h, |x, y| (h - 1 - y) * w + x)
+    }
+
+    pub fn display(&self) {
+        self.display_with_mapping(|v| format!("{v}"));
+    }
+    pub fn display_with_mapping<F>(&self, mapping: F)
+    where
+        F: Fn(T) -> String,
+    {
+        for (dy, row) in self.data.chunks(self.width.max(1)).enumerate() {
+            let mut line = String::new();
+            line.push_str(&format!("{:04} ", self.min_y + dy as isize));
+            for v in row {
+                line.push_str(&mapping(v.to_owned()));
+            }
+            println!("{line}");
+        }
+    }
+}
+
+/// A rectangular window onto a [`DenseMatrix`], addressed relative to its
+/// top left cell.
+#[derive(Debug, Clone, Copy)]
+pub struct View<'a, T> {
+    matrix: &'a DenseMatrix<T>,
+    x: isize,
+    y: isize,
+    width: usize,
+    height: usize,
+}
+impl<'a, T> View<'a, T>
+where
+    T: Default + Display + Clone,
+{
+    /// The position of the top left cell within the underlying grid.
+    pub fn origin(&self) -> (isize, isize) {
+        (self.x, self.y)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, dx: usize, dy: usize) -> Option<&'a T> {
+        if dx >= self.width || dy >= self.height {
+            return None;
+        }
+        self.matrix.get(self.x + dx as isize, self.y + dy as isize)
+    }
+
+    /// Every row of the view, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let (matrix, x, width) = (self.matrix, self.x, self.width);
+        (self.y..self.y + self.height as isize).map(move |y| {
+            let start = matrix.index_of(x, y).unwrap();
+            &matrix.data[start..start + width]
+        })
+    }
+
+    /// Copy the view into a grid of its own, at the origin.
+    pub fn to_matrix(&self) -> DenseMatrix<T> {
+        DenseMatrix {
+            data: self.rows().flat_map(|row| row.iter().cloned()).collect(),
+            width: self.width,
+            height: self.height,
+            min_x: 0,
+            min_y: 0,
+        }
+    }
+}
+
+impl<T> Index<(isize, isize)> for DenseMatrix<T>
+where
+    T: Default + Display + Clone,
+{
+    type Output = T;
+
+    fn index(&self, (x, y): (isize, isize)) -> &Self::Output {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("({x}, {y}) is outside the grid"))
+    }
+}
+impl<T> IndexMut<(isize, isize)> for DenseMatrix<T>
+where
+    T: Default + Display + Clone,
+{
+    fn index_mut(&mut self, (x, y): (isize, isize)) -> &mut Self::Output {
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({x}, {y}) is outside the grid"))
+    }
+}
+
+impl<T> DenseMatrix<T>
+where
+    T: TryFrom<char> + Default + Display + Clone,
+{
+    /// Read `lines` of characters, one row per line, with `(0, 0)` at the top
+    /// left. Errors point at the lines as numbered in the input.
+    pub fn from_lines(lines: &[Line]) -> Result<Self, ParseError> {
+        let width = lines
+            .iter()
+            .map(|line| line.text.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut grid = Self::with_size(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            for (x, (i, c)) in line.text.char_indices().enumerate() {
+                let value = T::try_from(c)
+                    .map_err(|_| line.error(&line.text[i..i + c.len_utf8()], "a grid cell"))?;
+                grid[(x as isize, y as isize)] = value;
+            }
+        }
+        Ok(grid)
+    }
+}
+
+/// Read a block of characters, one row per line, with `(0, 0)` at the top
+/// left.
+impl<T> FromStr for DenseMatrix<T>
+where
+    T: TryFrom<char> + Default + Display + Clone,
+{
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s
+            .lines()
+            .enumerate()
+            .map(|(id, text)| Line::new(id + 1, text))
+            .collect::<Vec<_>>();
+        Self::from_lines(&lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_and_set() {
+        let mut matrix = DenseMatrix::new();
+        assert!(matrix.is_empty());
+        matrix.set(2, 1, 5);
+        assert_eq!((3, 2), (matrix.width(), matrix.height()));
+        assert_eq!(Some(&5i64), matrix.get(2, 1));
+        assert_eq!(Some(&0), matrix.get(0, 0));
+        assert_eq!(None, matrix.get(3, 1));
+        assert_eq!(None, matrix.get(-1, 0));
+
+        matrix.set(-1, 3, 7);
+        assert_eq!((-1, 0), (matrix.min_x(), matrix.min_y()));
+        assert_eq!((2, 3), matrix.dimensions());
+        assert_eq!(5, matrix[(2, 1)]);
+        assert_eq!(7, matrix[(-1, 3)]);
+        assert_eq!(16, matrix.len());
+        assert_eq!(
+            vec![((2, 1), &5), ((-1, 3), &7)],
+            matrix.iter().filter(|(_, v)| **v != 0).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn transformations() {
+        let matrix = "abc\ndef".parse::<DenseMatrix<char>>().unwrap();
+        let text = |m: &DenseMatrix<char>| {
+            m.rows()
+                .map(|row| row.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        assert_eq!("ad\nbe\ncf", text(&matrix.transpose()));
+        assert_eq!("da\neb\nfc", text(&matrix.rotate_90()));
+        assert_eq!("fed\ncba", text(&matrix.rotate_180()));
+        assert_eq!("cf\nbe\nad", text(&matrix.rotate_270()));
+        assert_eq!("cba\nfed", text(&matrix.flip_horizontal()));
+        assert_eq!("def\nabc", text(&matrix.flip_vertical()));
+        assert_eq!(
+            matrix,
+            matrix.rotate_90().rotate_90().rotate_90().rotate_90()
+        );
+
+        assert_eq!(Some(&['d', 'e', 'f'][..]), matrix.row(1));
+        assert_eq!(None, matrix.row(2));
+        assert_eq!("be", matrix.column(1).unwrap().collect::<String>());
+        assert!(matrix.column(3).is_none());
+    }
+
+    #[test]
+    fn windows() {
+        let matrix = "abc\ndef\nghi".parse::<DenseMatrix<char>>().unwrap();
+        let window = matrix.window(1, 1, 2, 2).unwrap();
+        assert_eq!((1, 1), window.origin());
+        assert_eq!(Some(&'i'), window.get(1, 1));
+        assert_eq!(None, window.get(2, 1));
+        assert_eq!("ef\nhi", {
+            let m = window.to_matrix();
+            m.rows()
+                .map(|row| row.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        assert!(matrix.window(2, 2, 2, 1).is_none());
+        assert_eq!(4, matrix.windows(2, 2).count());
+        assert_eq!(
+            vec!['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i'],
+            matrix
+                .windows(1, 1)
+                .map(|w| *w.get(0, 0).unwrap())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        let matrix = "#.\n.#".parse::<DenseMatrix<char>>().unwrap();
+        assert_eq!((1, 1), matrix.dimensions());
+        assert_eq!('#', matrix[(1, 1)]);
+        assert_eq!('.', matrix[(0, 1)]);
+
+        // Errors keep the line numbers of the input
+        let lines = [Line::new(7, "12"), Line::new(8, "3→")];
+        let Err(ParseError::Syntax { line, column, .. }) = DenseMatrix::<u8>::from_lines(&lines)
+        else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!((8, 2), (line, column));
+    }
+}
@@ -0,0 +1,157 @@
+//! Exact geometry on the integer lattice, using 128-bit arithmetic
+//! throughout: measuring simple polygons, and where moving points' paths
+//! cross.
+use std::{
+    hash::Hash,
+    iter::Step,
+    ops::{Add, AddAssign, Sub},
+};
+
+use crate::{
+    math::{greatest_common_divisor, Rational},
+    Point,
+};
+
+mod ray;
+
+pub use ray::{Intersection, Ray};
+
+fn wide<T: TryInto<i128>>(value: T) -> i128 {
+    match value.try_into() {
+        Ok(value) => value,
+        Err(_) => panic!("coordinate out of range"),
+    }
+}
+
+/// A simple polygon on the integer lattice, held as its vertices in order
+/// (either way round); the last vertex joins back to the first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<(i128, i128)>,
+}
+
+impl Polygon {
+    pub fn new<T>(vertices: impl IntoIterator<Item = Point<T>>) -> Self
+    where
+        T: TryInto<i128>
+            + Default
+            + Step
+            + Sized
+            + Copy
+            + Sub<Output = T>
+            + Add<Output = T>
+            + AddAssign
+            + Eq
+            + PartialEq
+            + Hash,
+    {
+        Self {
+            vertices: vertices
+                .into_iter()
+                .map(|point| (wide(point.x()), wide(point.y())))
+                .collect(),
+        }
+    }
+
+    /// The polygon traced by moving `length` times by each `(dx, dy)` in
+    /// turn, starting from the origin.
+    pub fn from_runs(runs: impl IntoIterator<Item = ((i128, i128), i128)>) -> Self {
+        let mut position = (0, 0);
+        let mut vertices = Vec::new();
+        for ((dx, dy), length) in runs {
+            vertices.push(position);
+            position = (position.0 + dx * length, position.1 + dy * length);
+        }
+        Self { vertices }
+    }
+
+    pub fn vertices(&self) -> &[(i128, i128)] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = ((i128, i128), (i128, i128))> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+    }
+
+    /// Twice the signed area, which is always a whole number: positive when
+    /// the vertices run anticlockwise with `y` pointing up.
+    pub fn twice_signed_area(&self) -> i128 {
+        // Shoelace formula
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+            .sum()
+    }
+
+    /// The signed area; see [`Self::twice_signed_area`].
+    pub fn signed_area(&self) -> Rational {
+        Rational::new(self.twice_signed_area(), 2)
+    }
+
+    /// The length of the boundary.
+    pub fn perimeter(&self) -> f64 {
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| ((x2 - x1) as f64).hypot((y2 - y1) as f64))
+            .sum()
+    }
+
+    /// The number of lattice points on the boundary.
+    pub fn boundary_points(&self) -> i128 {
+        self.edges()
+            .map(|((x1, y1), (x2, y2))| greatest_common_divisor(x2 - x1, y2 - y1).unwrap())
+            .sum()
+    }
+
+    /// The number of lattice points strictly inside, by Pick's theorem.
+    pub fn interior_points(&self) -> i128 {
+        // A = I + B / 2 - 1
+        (self.twice_signed_area().abs() - self.boundary_points() + 2) / 2
+    }
+
+    /// The number of lattice points inside or on the boundary.
+    pub fn lattice_points(&self) -> i128 {
+        self.interior_points() + self.boundary_points()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn square() {
+        let square = Polygon::new([
+            Point::new(0, 0),
+            Point::new(4isize, 0),
+            Point::new(4, 4),
+            Point::new(0, 4),
+        ]);
+        assert_eq!(32, square.twice_signed_area());
+        assert_eq!(Rational::integer(16), square.signed_area());
+        assert_eq!(16.0, square.perimeter());
+        assert_eq!(16, square.boundary_points());
+        assert_eq!(9, square.interior_points());
+        assert_eq!(25, square.lattice_points());
+    }
+
+    #[test]
+    fn runs() {
+        // A right triangle traced clockwise with y pointing up
+        let triangle = Polygon::from_runs([((0, 1), 3), ((1, -1), 3), ((-1, 0), 3)]);
+        assert_eq!(vec![(0, 0), (0, 3), (3, 0)], triangle.vertices());
+        assert_eq!(Rational::new(-9, 2), triangle.signed_area());
+        assert_eq!(9, triangle.boundary_points());
+        assert_eq!(1, triangle.interior_points());
+
+        // Large enough to overflow 64-bit intermediate products
+        let huge = Polygon::from_runs([
+            ((1, 0), 1 << 40),
+            ((0, 1), 1 << 40),
+            ((-1, 0), 1 << 40),
+            ((0, -1), 1 << 40),
+        ]);
+        assert_eq!(((1 << 40) + 1) * ((1 << 40) + 1), huge.lattice_points());
+    }
+}
@@ -0,0 +1,227 @@
+use crate::math::Rational;
+
+/// Something starting at `origin` and moving by `velocity` each unit of
+/// time, in `N` dimensions.
+///
+/// Taking every time gives a line, only `t >= 0` a ray, and only
+/// `0 <= t <= 1` the segment from `origin` to `origin + velocity`. The
+/// velocity should not be zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ray<const N: usize> {
+    pub origin: [i128; N],
+    pub velocity: [i128; N],
+}
+
+/// How the paths of two [`Ray`]s meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intersection<const N: usize> {
+    /// The paths cross once, at `point`: time `t` along the first and `u`
+    /// along the second.
+    Crossing {
+        point: [Rational; N],
+        t: Rational,
+        u: Rational,
+    },
+    /// The lines cross, but at a time outside the ray or segment of one or
+    /// both, such as in the past.
+    Missed {
+        point: [Rational; N],
+        t: Rational,
+        u: Rational,
+    },
+    /// The lines are distinct and parallel, so never meet.
+    Parallel,
+    /// The lines are neither parallel nor meet, which needs at least three
+    /// dimensions.
+    Skew,
+    /// Both lie along the same line, and `overlapping` if they share any
+    /// point within their rays or segments.
+    Collinear { overlapping: bool },
+}
+
+/// The component of `a × b` in the plane of axes `i` and `j`.
+fn cross<const N: usize>(a: &[i128; N], b: &[i128; N], i: usize, j: usize) -> i128 {
+    a[i] * b[j] - a[j] * b[i]
+}
+
+fn axis_pairs(n: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..n).flat_map(move |i| (i + 1..n).map(move |j| (i, j)))
+}
+
+fn is_parallel<const N: usize>(a: &[i128; N], b: &[i128; N]) -> bool {
+    axis_pairs(N).all(|(i, j)| cross(a, b, i, j) == 0)
+}
+
+impl<const N: usize> Ray<N> {
+    pub fn new(origin: [i128; N], velocity: [i128; N]) -> Self {
+        Self { origin, velocity }
+    }
+
+    /// The position at time `t`.
+    pub fn at(&self, t: Rational) -> [Rational; N] {
+        std::array::from_fn(|axis| {
+            Rational::integer(self.origin[axis]) + t * Rational::integer(self.velocity[axis])
+        })
+    }
+
+    /// Where the two lines through the rays meet, at any time.
+    pub fn line_intersection(&self, other: &Self) -> Intersection<N> {
+        let offset: [i128; N] = std::array::from_fn(|axis| other.origin[axis] - self.origin[axis]);
+        // Any plane in which the velocities are not parallel pins down both times
+        let Some((i, j)) =
+            axis_pairs(N).find(|(i, j)| cross(&self.velocity, &other.velocity, *i, *j) != 0)
+        else {
+            return if is_parallel(&offset, &self.velocity) {
+                Intersection::Collinear { overlapping: true }
+            } else {
+                Intersection::Parallel
+            };
+        };
+        let denominator = cross(&self.velocity, &other.velocity, i, j);
+        let t = Rational::new(cross(&offset, &other.velocity, i, j), denominator);
+        let u = Rational::new(cross(&offset, &self.velocity, i, j), denominator);
+        let point = self.at(t);
+        // ... but the crossing in that plane must hold in every other axis too
+        if point != other.at(u) {
+            return Intersection::Skew;
+        }
+        Intersection::Crossing { point, t, u }
+    }
+
+    /// Where the two rays meet, only counting times from zero onwards.
+    pub fn ray_intersection(&self, other: &Self) -> Intersection<N> {
+        self.restricted_intersection(other, None)
+    }
+
+    /// Where the two segments meet, only counting times from zero to one.
+    pub fn segment_intersection(&self, other: &Self) -> Intersection<N> {
+        self.restricted_intersection(other, Some(Rational::ONE))
+    }
+
+    /// The intersection of the lines, limited to times from zero up to `end`,
+    /// or forever if `None`.
+    fn restricted_intersection(&self, other: &Self, end: Option<Rational>) -> Intersection<N> {
+        let during = |time: Rational| time >= Rational::ZERO && end.is_none_or(|end| time <= end);
+        match self.line_intersection(other) {
+            Intersection::Crossing { point, t, u } if !(during(t) && during(u)) => {
+                Intersection::Missed { point, t, u }
+            }
+            Intersection::Collinear { .. } => Intersection::Collinear {
+                overlapping: self.overlaps(other, end),
+            },
+            intersection => intersection,
+        }
+    }
+
+    /// Whether collinear `self` and `other` share any point at times from
+    /// zero up to `end`, or forever if `None`.
+    fn overlaps(&self, other: &Self, end: Option<Rational>) -> bool {
+        let Some(axis) = (0..N).find(|axis| self.velocity[*axis] != 0) else {
+            return if other.velocity.iter().all(|v| *v == 0) {
+                self.origin == other.origin
+            } else {
+                other.overlaps(self, end)
+            };
+        };
+        // The time along `self` at which it reaches `other`'s position at `u`
+        let along = |u: Rational| {
+            (Rational::integer(other.origin[axis] - self.origin[axis])
+                + u * Rational::integer(other.velocity[axis]))
+                / Rational::integer(self.velocity[axis])
+        };
+        let start = along(Rational::ZERO);
+        let (low, high) = match end {
+            Some(end) => {
+                let finish = along(end);
+                (Some(start.min(finish)), Some(start.max(finish)))
+            }
+            None => match self.velocity[axis].signum() * other.velocity[axis].signum() {
+                1 => (Some(start), None),
+                -1 => (None, Some(start)),
+                _ => (Some(start), Some(start)),
+            },
+        };
+        // ... which must also be a time within `self`
+        let low = low.map_or(Rational::ZERO, |low| low.max(Rational::ZERO));
+        let high = match (high, end) {
+            (Some(high), Some(end)) => Some(high.min(end)),
+            (high, end) => high.or(end),
+        };
+        high.is_none_or(|high| low <= high)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rational(numerator: i128, denominator: i128) -> Rational {
+        Rational::new(numerator, denominator)
+    }
+
+    #[test]
+    fn plane() {
+        let a = Ray::new([19, 13], [-2, 1]);
+        let b = Ray::new([18, 19], [-1, -1]);
+        assert_eq!(
+            Intersection::Crossing {
+                point: [rational(43, 3), rational(46, 3)],
+                t: rational(7, 3),
+                u: rational(11, 3),
+            },
+            a.ray_intersection(&b)
+        );
+        // Crosses before the first ray starts
+        let c = Ray::new([20, 19], [1, -5]);
+        assert!(matches!(
+            a.ray_intersection(&c),
+            Intersection::Missed { t, .. } if t == rational(-11, 9)
+        ));
+        assert!(matches!(
+            a.line_intersection(&c),
+            Intersection::Crossing { .. }
+        ));
+
+        let d = Ray::new([20, 25], [-2, -2]);
+        assert_eq!(Intersection::Parallel, b.ray_intersection(&d));
+    }
+
+    #[test]
+    fn space() {
+        let a = Ray::new([0, 0, 0], [1, 1, 1]);
+        let b = Ray::new([2, 0, 0], [-1, 1, 1]);
+        assert_eq!(
+            Intersection::Crossing {
+                point: [Rational::ONE; 3],
+                t: Rational::ONE,
+                u: Rational::ONE,
+            },
+            a.segment_intersection(&b)
+        );
+
+        let c = Ray::new([0, 0, 0], [1, 0, 0]);
+        let d = Ray::new([0, 1, 1], [0, 1, 0]);
+        assert_eq!(Intersection::Skew, c.line_intersection(&d));
+    }
+
+    #[test]
+    fn collinear() {
+        let a = Ray::new([0, 0], [1, 1]);
+        let ahead = Ray::new([5, 5], [1, 1]);
+        let towards = Ray::new([5, 5], [-1, -1]);
+        let away = Ray::new([-5, -5], [-1, -1]);
+        let overlapping = Intersection::Collinear { overlapping: true };
+        let apart = Intersection::Collinear { overlapping: false };
+
+        assert_eq!(overlapping, a.line_intersection(&away));
+        assert_eq!(overlapping, a.ray_intersection(&ahead));
+        assert_eq!(overlapping, a.ray_intersection(&towards));
+        assert_eq!(apart, a.ray_intersection(&away));
+        assert_eq!(apart, a.segment_intersection(&ahead));
+
+        let long = Ray::new([0, 0], [2, 2]);
+        let back = Ray::new([3, 3], [-2, -2]);
+        assert_eq!(overlapping, long.segment_intersection(&back));
+        assert_eq!(overlapping, back.segment_intersection(&long));
+    }
+}
@@ -1,12 +1,19 @@
-use std::cmp::{Eq, PartialOrd};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::cmp::{Eq, Ordering};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::fmt::Debug;
 use std::hash::Hash;
-use std::iter::Iterator;
 use std::marker::Copy;
 use std::ops::Add;
 use tracing::debug;
 
+mod dominator;
+mod junction;
+mod undirected;
+
+pub use dominator::DominatorTree;
+pub use junction::JunctionGraph;
+pub use undirected::{Cut, UndirectedGraph};
+
 fn breadth_first_search_build_path<'a, N>(
     mut position: &'a N,
     path_fragments: &'a HashMap<N, N>,
@@ -73,74 +80,510 @@ where
     None
 }
 
-pub fn dijkstra<N, IS, IE, GN, NEIGH, R>(
-    nodes: &Vec<N>,
-    initial_score: IS,
+/// The number of steps from `start` to every node reachable from it, going no
+/// further than `max_depth` steps if given.
+pub fn bfs_distances<N, GN>(
+    start: N,
+    get_neighbours: GN,
+    max_depth: Option<usize>,
+) -> HashMap<N, usize>
+where
+    N: Debug + Eq + Hash + Clone,
+    GN: Fn(&N) -> Vec<N>,
+{
+    let mut distances = HashMap::new();
+    distances.insert(start.clone(), 0);
+    let mut queue = VecDeque::new();
+    queue.push_back((start, 0));
+
+    while let Some((current, depth)) = queue.pop_front() {
+        if max_depth.is_some_and(|max_depth| depth >= max_depth) {
+            continue;
+        }
+        for neighbour in get_neighbours(&current) {
+            if distances.contains_key(&neighbour) {
+                continue;
+            }
+            distances.insert(neighbour.clone(), depth + 1);
+            queue.push_back((neighbour, depth + 1));
+        }
+    }
+    debug!(reachable = distances.len(), max_depth, "distances");
+    distances
+}
+
+/// The nodes of `distances` which can be stood on after exactly `depth`
+/// steps.
+///
+/// This assumes every step can be undone and the graph is bipartite, as on a
+/// grid, so a node can be reached in exactly `depth` steps whenever it is
+/// no further away and its distance has the same parity.
+pub fn at_exact_depth<N>(distances: &HashMap<N, usize>, depth: usize) -> impl Iterator<Item = &N> {
+    distances
+        .iter()
+        .filter(move |(_, distance)| **distance <= depth && **distance % 2 == depth % 2)
+        .map(|(node, _)| node)
+}
+
+/// The nodes which can be stood on after exactly `depth` steps from `start`;
+/// see [`at_exact_depth`].
+pub fn reachable_at_depth<N, GN>(start: N, get_neighbours: GN, depth: usize) -> HashSet<N>
+where
+    N: Debug + Eq + Hash + Clone,
+    GN: Fn(&N) -> Vec<N>,
+{
+    let distances = bfs_distances(start, get_neighbours, Some(depth));
+    at_exact_depth(&distances, depth).cloned().collect()
+}
+
+/// The cheapest route found by [`dijkstra`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPath<N, C>
+where
+    N: Hash + Eq,
+{
+    /// Total cost of the route.
+    pub cost: C,
+    /// Every node on the route, from the start to the end inclusive.
+    pub path: Vec<N>,
+    /// The node each node discovered during the search was reached from.
+    pub predecessors: HashMap<N, N>,
+}
+
+/// The cheapest cost to every node reachable from a start, as found by
+/// [`dijkstra_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPaths<N, C>
+where
+    N: Hash + Eq,
+{
+    /// Cost of reaching each node, including the start at zero cost.
+    pub costs: HashMap<N, C>,
+    /// The node each node (other than the start) was reached from.
+    pub predecessors: HashMap<N, N>,
+}
+impl<N, C> ShortestPaths<N, C>
+where
+    N: Hash + Eq + Clone,
+{
+    /// The cheapest route from the start to `end`, if it was reachable.
+    pub fn path_to(&self, end: &N) -> Option<Vec<N>> {
+        self.costs
+            .contains_key(end)
+            .then(|| build_path(end.clone(), &self.predecessors))
+    }
+}
+
+/// Walk `predecessors` back from `end`, returning the route from the start.
+fn build_path<N>(end: N, predecessors: &HashMap<N, N>) -> Vec<N>
+where
+    N: Hash + Eq + Clone,
+{
+    let mut path = vec![end];
+    while let Some(previous) = predecessors.get(path.last().unwrap()) {
+        path.push(previous.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// An entry in the dijkstra queue, ordered so that the cheapest is popped
+/// first from the max-heap.
+struct Queued<N, C> {
+    cost: C,
+    node: N,
+}
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.cmp(&self.cost)
+    }
+}
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+/// Settle nodes in order of cost until `is_end` accepts one, returning that
+/// node along with the costs and predecessors recorded so far.
+fn dijkstra_search<N, C, IE, GN, NEIGH>(
+    start: N,
     get_neighbours: GN,
     is_end: IE,
-) -> Option<R>
+) -> (Option<N>, HashMap<N, C>, HashMap<N, N>)
 where
-    IS: Fn(&N) -> Option<R>,
-    GN: Fn(&N) -> NEIGH,
+    N: Debug + Eq + Hash + Clone,
+    C: Debug + Ord + Copy + Add<Output = C> + Default,
     IE: Fn(&N) -> bool,
-    N: Debug + Eq + Copy + Hash,
-    R: Debug + PartialOrd + Copy + Add<Output = R> + HasOne,
-    NEIGH: Iterator<Item = N>,
+    GN: Fn(&N) -> NEIGH,
+    NEIGH: IntoIterator<Item = (N, C)>,
 {
-    let mut scores = HashMap::new();
-    for node in nodes {
-        if let Some(s) = initial_score(node) {
-            scores.insert(*node, s);
+    let mut costs = HashMap::new();
+    let mut predecessors = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    costs.insert(start.clone(), C::default());
+    queue.push(Queued {
+        cost: C::default(),
+        node: start,
+    });
+
+    while let Some(Queued { cost, node }) = queue.pop() {
+        // Skip stale entries superseded by a cheaper route
+        if costs.get(&node).is_some_and(|best| cost > *best) {
+            continue;
         }
-    }
-    let mut visited = HashSet::new();
-    let result = loop {
-        // Find smallest, unvisited
-        let mut bestnode = None;
-        let mut bestscore = None;
-        for (node, score) in scores.iter() {
-            if !visited.contains(node) {
-                match bestscore {
-                    None => {
-                        bestnode = Some(node.to_owned());
-                        bestscore = Some(score.to_owned());
-                    }
-                    Some(s) if s > *score => {
-                        bestnode = Some(*node);
-                        bestscore = Some(score.to_owned());
-                    }
-                    Some(_) => {}
-                }
+        debug!(node = debug(&node), cost = debug(&cost), "settled");
+        if is_end(&node) {
+            return (Some(node), costs, predecessors);
+        }
+        for (neighbour, weight) in get_neighbours(&node) {
+            let candidate = cost + weight;
+            if costs.get(&neighbour).is_some_and(|best| candidate >= *best) {
+                continue;
             }
+            costs.insert(neighbour.clone(), candidate);
+            predecessors.insert(neighbour.clone(), node.clone());
+            queue.push(Queued {
+                cost: candidate,
+                node: neighbour,
+            });
         }
-        if bestnode.is_none() {
-            break None;
+    }
+    (None, costs, predecessors)
+}
+
+/// Find the cheapest route from `start` to the first node satisfying
+/// `is_end`.
+///
+/// `get_neighbours` yields each neighbour along with the (non-negative) cost
+/// of the edge to it; costs start from `C::default()`.
+pub fn dijkstra<N, C, IE, GN, NEIGH>(
+    start: N,
+    get_neighbours: GN,
+    is_end: IE,
+) -> Option<ShortestPath<N, C>>
+where
+    N: Debug + Eq + Hash + Clone,
+    C: Debug + Ord + Copy + Add<Output = C> + Default,
+    IE: Fn(&N) -> bool,
+    GN: Fn(&N) -> NEIGH,
+    NEIGH: IntoIterator<Item = (N, C)>,
+{
+    let (end, costs, predecessors) = dijkstra_search(start, get_neighbours, is_end);
+    let end = end?;
+    Some(ShortestPath {
+        cost: costs[&end],
+        path: build_path(end, &predecessors),
+        predecessors,
+    })
+}
+
+/// Find the cheapest cost to every node reachable from `start`.
+pub fn dijkstra_all<N, C, GN, NEIGH>(start: N, get_neighbours: GN) -> ShortestPaths<N, C>
+where
+    N: Debug + Eq + Hash + Clone,
+    C: Debug + Ord + Copy + Add<Output = C> + Default,
+    GN: Fn(&N) -> NEIGH,
+    NEIGH: IntoIterator<Item = (N, C)>,
+{
+    let (_, costs, predecessors) = dijkstra_search(start, get_neighbours, |_| false);
+    ShortestPaths {
+        costs,
+        predecessors,
+    }
+}
+
+/// The route found by [`a_star`] or [`bidirectional_breadth_first_search`],
+/// with the number of nodes expanded along the way for diagnostics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult<N, C> {
+    /// Every node on the route, from the start to the end inclusive.
+    pub path: Vec<N>,
+    /// Total cost of the route.
+    pub cost: C,
+    /// How many nodes had their neighbours generated.
+    pub expanded: usize,
+}
+
+/// Find the cheapest route from `start` to the first node satisfying
+/// `is_end`, guided by `heuristic`.
+///
+/// The heuristic must never overestimate the remaining cost to an end node,
+/// otherwise the route found may not be the cheapest.
+pub fn a_star<N, C, IE, GN, H, NEIGH>(
+    start: N,
+    get_neighbours: GN,
+    heuristic: H,
+    is_end: IE,
+) -> Option<SearchResult<N, C>>
+where
+    N: Debug + Eq + Hash + Clone,
+    C: Debug + Ord + Copy + Add<Output = C> + Default,
+    IE: Fn(&N) -> bool,
+    GN: Fn(&N) -> NEIGH,
+    H: Fn(&N) -> C,
+    NEIGH: IntoIterator<Item = (N, C)>,
+{
+    let mut costs = HashMap::new();
+    let mut predecessors = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    let mut expanded = 0;
+    costs.insert(start.clone(), C::default());
+    queue.push(Queued {
+        cost: heuristic(&start),
+        node: (C::default(), start),
+    });
+
+    while let Some(Queued {
+        node: (cost, node), ..
+    }) = queue.pop()
+    {
+        // Skip stale entries superseded by a cheaper route
+        if costs.get(&node).is_some_and(|best| cost > *best) {
+            continue;
+        }
+        if is_end(&node) {
+            debug!(node = debug(&node), expanded, "reached end");
+            return Some(SearchResult {
+                path: build_path(node, &predecessors),
+                cost,
+                expanded,
+            });
         }
-        let bestnode = bestnode.unwrap();
-        let bestscore = bestscore.unwrap();
-        visited.insert(bestnode);
-        if is_end(&bestnode) {
-            break Some(bestscore);
+        expanded += 1;
+        for (neighbour, weight) in get_neighbours(&node) {
+            let candidate = cost + weight;
+            if costs.get(&neighbour).is_some_and(|best| candidate >= *best) {
+                continue;
+            }
+            costs.insert(neighbour.clone(), candidate);
+            predecessors.insert(neighbour.clone(), node.clone());
+            queue.push(Queued {
+                cost: candidate + heuristic(&neighbour),
+                node: (candidate, neighbour),
+            });
         }
-        let neighbours = get_neighbours(&bestnode);
-        for neighbour in neighbours {
-            let n = neighbour;
-            let score = scores.entry(n).or_insert(bestscore + R::one());
-            if *score > bestscore + R::one() {
-                *score = bestscore + R::one();
+    }
+    None
+}
+
+/// Expand every node in `frontier` by one step, returning the next frontier
+/// along with the shortest meeting point with the other search, if any.
+fn bidirectional_expand<N, GN>(
+    frontier: Vec<N>,
+    get_neighbours: &GN,
+    seen: &mut HashMap<N, (Option<N>, usize)>,
+    other: &HashMap<N, (Option<N>, usize)>,
+    expanded: &mut usize,
+) -> (Vec<N>, Option<(N, usize)>)
+where
+    N: Debug + Eq + Hash + Clone,
+    GN: Fn(&N) -> Vec<N>,
+{
+    let mut next = Vec::new();
+    let mut meeting: Option<(N, usize)> = None;
+    for current in frontier {
+        *expanded += 1;
+        let depth = seen[&current].1 + 1;
+        for neighbour in get_neighbours(&current) {
+            if seen.contains_key(&neighbour) {
+                continue;
+            }
+            seen.insert(neighbour.clone(), (Some(current.clone()), depth));
+            if let Some((_, remaining)) = other.get(&neighbour) {
+                if meeting
+                    .as_ref()
+                    .is_none_or(|(_, best)| depth + remaining < *best)
+                {
+                    meeting = Some((neighbour.clone(), depth + remaining));
+                }
             }
+            next.push(neighbour);
         }
-    };
-    debug!("{:?}", scores);
-    result
+    }
+    (next, meeting)
 }
 
-pub trait HasOne {
-    fn one() -> Self;
+/// Find the shortest route from `start` to `end` by searching outwards from
+/// both, a layer at a time, until the two searches meet.
+///
+/// `get_neighbours` gives the nodes reachable from a node, and
+/// `get_previous` the nodes from which it can be reached; for an undirected
+/// graph they are the same.
+pub fn bidirectional_breadth_first_search<N, GN, GP>(
+    start: N,
+    end: N,
+    get_neighbours: GN,
+    get_previous: GP,
+) -> Option<SearchResult<N, usize>>
+where
+    N: Debug + Eq + Hash + Clone,
+    GN: Fn(&N) -> Vec<N>,
+    GP: Fn(&N) -> Vec<N>,
+{
+    if start == end {
+        return Some(SearchResult {
+            path: vec![start],
+            cost: 0,
+            expanded: 0,
+        });
+    }
+    let mut forward = HashMap::from([(start.clone(), (None, 0))]);
+    let mut backward = HashMap::from([(end.clone(), (None, 0))]);
+    let mut forward_frontier = vec![start];
+    let mut backward_frontier = vec![end];
+    let mut expanded = 0;
+
+    while !forward_frontier.is_empty() && !backward_frontier.is_empty() {
+        // Grow whichever side has less work to do
+        let meeting = if forward_frontier.len() <= backward_frontier.len() {
+            let (next, meeting) = bidirectional_expand(
+                forward_frontier,
+                &get_neighbours,
+                &mut forward,
+                &backward,
+                &mut expanded,
+            );
+            forward_frontier = next;
+            meeting
+        } else {
+            let (next, meeting) = bidirectional_expand(
+                backward_frontier,
+                &get_previous,
+                &mut backward,
+                &forward,
+                &mut expanded,
+            );
+            backward_frontier = next;
+            meeting
+        };
+        if let Some((middle, cost)) = meeting {
+            debug!(middle = debug(&middle), cost, expanded, "searches met");
+            let mut path = Vec::new();
+            let mut position = Some(middle.clone());
+            while let Some(current) = position {
+                position = forward[&current].0.clone();
+                path.push(current);
+            }
+            path.reverse();
+            let mut position = backward[&middle].0.clone();
+            while let Some(current) = position {
+                position = backward[&current].0.clone();
+                path.push(current);
+            }
+            return Some(SearchResult {
+                path,
+                cost,
+                expanded,
+            });
+        }
+    }
+    None
 }
 
-impl HasOne for i64 {
-    fn one() -> Self {
-        1
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn edges(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 7), ('c', 9), ('f', 14)],
+            'b' => vec![('c', 10), ('d', 15)],
+            'c' => vec![('d', 11), ('f', 2)],
+            'd' => vec![('e', 6)],
+            'f' => vec![('e', 9)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn shortest_path() {
+        let r = dijkstra('a', edges, |n| *n == 'e').unwrap();
+        assert_eq!(20, r.cost);
+        assert_eq!(vec!['a', 'c', 'f', 'e'], r.path);
+        assert_eq!(Some(&'a'), r.predecessors.get(&'c'));
+
+        let r = dijkstra('a', edges, |n| *n == 'a').unwrap();
+        assert_eq!((0, vec!['a']), (r.cost, r.path));
+
+        assert!(dijkstra('d', edges, |n| *n == 'a').is_none());
+    }
+
+    #[test]
+    fn all_shortest_paths() {
+        let r = dijkstra_all('a', edges);
+        assert_eq!(6, r.costs.len());
+        assert_eq!(Some(&0), r.costs.get(&'a'));
+        assert_eq!(Some(&20), r.costs.get(&'d'));
+        assert_eq!(Some(vec!['a', 'c', 'd']), r.path_to(&'d'));
+        assert_eq!(None, r.path_to(&'z'));
+    }
+
+    #[test]
+    fn a_star_search() {
+        let r = a_star('a', edges, |_| 0, |n| *n == 'e').unwrap();
+        assert_eq!((20, vec!['a', 'c', 'f', 'e']), (r.cost, r.path));
+
+        // Manhattan distance on an open grid only expands the nodes en route
+        let grid = |&(x, y): &(i32, i32)| {
+            [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .into_iter()
+                .map(move |(dx, dy)| ((x + dx, y + dy), 1))
+                .filter(|((x, y), _)| (0..10).contains(x) && (0..10).contains(y))
+        };
+        let r = a_star((0, 0), grid, |(x, y)| (9 - x) + (9 - y), |n| *n == (9, 9)).unwrap();
+        assert_eq!(18, r.cost);
+        assert_eq!(19, r.path.len());
+        assert!(r.expanded < 100);
+        assert!(a_star((0, 0), grid, |_| 0, |n| *n == (10, 10)).is_none());
+    }
+
+    #[test]
+    fn bidirectional() {
+        let line = |n: &i32| vec![n - 1, n + 1];
+        let r = bidirectional_breadth_first_search(0, 5, line, line).unwrap();
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], r.path);
+        assert_eq!(5, r.cost);
+
+        // A one-way ring can only be walked backwards by the end search
+        let next = |n: &i32| vec![(n + 1) % 10];
+        let previous = |n: &i32| vec![(n + 9) % 10];
+        let r = bidirectional_breadth_first_search(7, 2, next, previous).unwrap();
+        assert_eq!(vec![7, 8, 9, 0, 1, 2], r.path);
+        assert_eq!(5, r.cost);
+
+        let r = bidirectional_breadth_first_search(3, 3, next, previous).unwrap();
+        assert_eq!((vec![3], 0), (r.path, r.cost));
+
+        let none = |_: &i32| vec![];
+        assert!(bidirectional_breadth_first_search(1, 2, none, none).is_none());
+    }
+
+    #[test]
+    fn distances() {
+        let line = |n: &i32| vec![n - 1, n + 1];
+        let r = bfs_distances(0, line, Some(3));
+        assert_eq!(7, r.len());
+        assert_eq!(Some(&3), r.get(&-3));
+
+        let ring = |n: &i32| vec![(n + 1) % 6, (n + 5) % 6];
+        let r = bfs_distances(0, ring, None);
+        assert_eq!(
+            vec![0, 1, 2, 3, 2, 1],
+            (0..6).map(|n| r[&n]).collect::<Vec<_>>()
+        );
+
+        let mut r = reachable_at_depth(0, line, 4)
+            .into_iter()
+            .collect::<Vec<_>>();
+        r.sort();
+        assert_eq!(vec![-4, -2, 0, 2, 4], r);
+        assert_eq!(3, at_exact_depth(&bfs_distances(0, ring, None), 5).count());
     }
 }
@@ -0,0 +1,139 @@
+/// The dominator tree of a directed acyclic graph, rooted at node 0: node
+/// `a` dominates node `b` if every path from the root to `b` passes through
+/// `a`.
+///
+/// In a pile of blocks with the ground as the root, a block dominates just
+/// those which would fall if it were taken away.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DominatorTree {
+    /// The immediate dominator of each node, which is `None` for the root
+    /// and nodes it cannot reach.
+    parents: Vec<Option<usize>>,
+    depths: Vec<usize>,
+}
+
+impl DominatorTree {
+    /// Build the tree from the `predecessors` of each node, which must be
+    /// numbered in topological order so that every predecessor comes before
+    /// its successors.
+    pub fn from_dag(predecessors: &[Vec<usize>]) -> Self {
+        let mut tree = Self {
+            parents: Vec::with_capacity(predecessors.len()),
+            depths: Vec::with_capacity(predecessors.len()),
+        };
+        for (node, before) in predecessors.iter().enumerate() {
+            // The immediate dominator is the deepest node which dominates
+            // every reachable predecessor
+            let parent = if node == 0 {
+                None
+            } else {
+                before
+                    .iter()
+                    .copied()
+                    .filter(|p| {
+                        assert!(*p < node, "predecessors must come first");
+                        tree.is_reachable(*p)
+                    })
+                    .reduce(|a, b| tree.common_dominator(a, b))
+            };
+            tree.depths.push(parent.map_or(0, |p| tree.depths[p] + 1));
+            tree.parents.push(parent);
+        }
+        tree
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    fn is_reachable(&self, node: usize) -> bool {
+        node == 0 || self.parents[node].is_some()
+    }
+
+    /// The closest node other than itself which dominates `node`.
+    pub fn immediate_dominator(&self, node: usize) -> Option<usize> {
+        self.parents[node]
+    }
+
+    /// The deepest node dominating both `a` and `b`.
+    fn common_dominator(&self, mut a: usize, mut b: usize) -> usize {
+        while a != b {
+            if self.depths[a] < self.depths[b] {
+                b = self.parents[b].unwrap();
+            } else {
+                a = self.parents[a].unwrap();
+            }
+        }
+        a
+    }
+
+    /// Whether `a` dominates `b`, which every node does itself.
+    pub fn dominates(&self, a: usize, b: usize) -> bool {
+        let mut node = Some(b);
+        while let Some(n) = node {
+            if n == a {
+                return true;
+            }
+            node = self.parents[n];
+        }
+        false
+    }
+
+    /// The number of nodes each node dominates, not counting itself.
+    pub fn dominated_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.len()];
+        // Children always come after their parents
+        for node in (0..self.len()).rev() {
+            if let Some(parent) = self.parents[node] {
+                counts[parent] += counts[node] + 1;
+            }
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dominators() {
+        // 0 -> 1 -> {2, 3} -> 4 -> 6, and 0 -> 5 -> 6; 7 is unreachable
+        let predecessors = vec![
+            vec![],
+            vec![0],
+            vec![1],
+            vec![1],
+            vec![2, 3],
+            vec![0],
+            vec![4, 5],
+            vec![],
+        ];
+        let tree = DominatorTree::from_dag(&predecessors);
+        let parents = (0..tree.len())
+            .map(|node| tree.immediate_dominator(node))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                None,
+                Some(0),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(0),
+                Some(0),
+                None
+            ],
+            parents
+        );
+        assert!(tree.dominates(1, 4));
+        assert!(tree.dominates(4, 4));
+        assert!(!tree.dominates(2, 4));
+        assert!(!tree.dominates(1, 6));
+        assert_eq!(vec![6, 3, 0, 0, 0, 0, 0, 0], tree.dominated_counts());
+    }
+}
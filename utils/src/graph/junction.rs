@@ -0,0 +1,335 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+use tracing::debug;
+
+/// A graph of junctions joined by weighted corridors, made by contracting
+/// every chain of nodes which have no choice of direction into one edge.
+#[derive(Debug, Clone)]
+pub struct JunctionGraph<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    edges: Vec<Vec<(usize, u64)>>,
+}
+
+impl<N> JunctionGraph<N>
+where
+    N: Debug + Eq + Hash + Clone,
+{
+    /// Contract the graph reachable from the first of `terminals`, which
+    /// take the first indices in the order given.
+    ///
+    /// A node is a junction if it is one of `terminals` or there is more than
+    /// one way on from it; every other node is part of a corridor, which is
+    /// walked without turning back until it reaches a junction. Corridors
+    /// which dead-end are dropped, and those which `get_neighbours` only
+    /// allows walking one way only produce an edge in that direction.
+    pub fn contract<GN>(terminals: &[N], get_neighbours: GN) -> Self
+    where
+        GN: Fn(&N) -> Vec<N>,
+    {
+        let mut graph = Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            edges: Vec::new(),
+        };
+        for terminal in terminals {
+            graph.insert(terminal.clone());
+        }
+        let mut queued = vec![false; graph.len()];
+        let mut queue = VecDeque::new();
+        if !terminals.is_empty() {
+            queued[0] = true;
+            queue.push_back(0);
+        }
+
+        while let Some(from) = queue.pop_front() {
+            let junction = graph.nodes[from].clone();
+            for first in get_neighbours(&junction) {
+                let Some((end, length)) =
+                    Self::walk(terminals, &get_neighbours, junction.clone(), first)
+                else {
+                    continue;
+                };
+                let to = graph.insert(end);
+                if to == from {
+                    continue;
+                }
+                if to >= queued.len() {
+                    queued.resize(to + 1, false);
+                }
+                if !queued[to] {
+                    queued[to] = true;
+                    queue.push_back(to);
+                }
+                // Keep only the longest of any parallel corridors
+                match graph.edges[from].iter_mut().find(|(n, _)| *n == to) {
+                    Some((_, weight)) => *weight = (*weight).max(length),
+                    None => graph.edges[from].push((to, length)),
+                }
+            }
+        }
+        debug!(
+            junctions = graph.len(),
+            edges = graph.edges.iter().map(Vec::len).sum::<usize>(),
+            "contracted"
+        );
+        graph
+    }
+
+    /// Follow the corridor from `previous` through `current` to the next
+    /// junction, returning it and the corridor length, or `None` if the
+    /// corridor dead-ends.
+    fn walk<GN>(
+        terminals: &[N],
+        get_neighbours: &GN,
+        mut previous: N,
+        mut current: N,
+    ) -> Option<(N, u64)>
+    where
+        GN: Fn(&N) -> Vec<N>,
+    {
+        let mut length = 1;
+        loop {
+            let neighbours = get_neighbours(&current);
+            if terminals.contains(&current) || neighbours.len() > 2 {
+                return Some((current, length));
+            }
+            let mut next = neighbours.into_iter().filter(|n| *n != previous);
+            match (next.next(), next.next()) {
+                (None, _) => return None,
+                (Some(n), None) => {
+                    previous = std::mem::replace(&mut current, n);
+                    length += 1;
+                }
+                // More than one way on makes this a junction too
+                (Some(_), Some(_)) => return Some((current, length)),
+            }
+        }
+    }
+
+    fn insert(&mut self, node: N) -> usize {
+        if let Some(index) = self.index.get(&node) {
+            return *index;
+        }
+        self.index.insert(node.clone(), self.nodes.len());
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        self.nodes.len() - 1
+    }
+}
+
+impl<N> JunctionGraph<N>
+where
+    N: Eq + Hash,
+{
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The junction at `index`.
+    pub fn node(&self, index: usize) -> &N {
+        &self.nodes[index]
+    }
+
+    /// The index of the junction `node`, if it is one.
+    pub fn index_of(&self, node: &N) -> Option<usize> {
+        self.index.get(node).copied()
+    }
+
+    /// The junctions reachable directly from `index`, with the length of the
+    /// corridor to each.
+    pub fn edges(&self, index: usize) -> &[(usize, u64)] {
+        &self.edges[index]
+    }
+
+    /// The length of the longest route from `start` to `end` which visits no
+    /// junction twice, or `None` if `end` cannot be reached.
+    ///
+    /// # Panics
+    ///
+    /// If the graph has more than 64 junctions.
+    pub fn longest_path(&self, start: &N, end: &N) -> Option<u64> {
+        let search = LongestPath::new(self, start, end)?;
+        let mut memo = HashMap::new();
+        search.longest(search.start, 1 << search.start, &mut memo)
+    }
+
+    /// As [`Self::longest_path`], but splitting the search across `threads`
+    /// threads.
+    pub fn longest_path_parallel(&self, start: &N, end: &N, threads: usize) -> Option<u64>
+    where
+        N: Sync,
+    {
+        let search = LongestPath::new(self, start, end)?;
+        // Expand the first few steps breadth first to have enough work to share
+        let mut tasks = vec![(search.start, 1u64 << search.start, 0)];
+        while tasks.len() < threads * 8 {
+            let mut next = Vec::new();
+            let mut expanded = false;
+            for (node, visited, length) in tasks {
+                if node == search.end {
+                    next.push((node, visited, length));
+                    continue;
+                }
+                for (neighbour, weight) in search.moves(node, visited) {
+                    expanded = true;
+                    next.push((neighbour, visited | 1 << neighbour, length + weight));
+                }
+            }
+            tasks = next;
+            if !expanded {
+                break;
+            }
+        }
+        debug!(tasks = tasks.len(), threads, "split search");
+
+        let chunk_size = tasks.len().div_ceil(threads.max(1)).max(1);
+        std::thread::scope(|scope| {
+            let handles = tasks
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let search = &search;
+                    scope.spawn(move || {
+                        let mut memo = HashMap::new();
+                        chunk
+                            .iter()
+                            .filter_map(|(node, visited, length)| {
+                                Some(length + search.longest(*node, *visited, &mut memo)?)
+                            })
+                            .max()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().unwrap())
+                .max()
+        })
+    }
+}
+
+/// A depth first search for the longest simple path, tracking visited
+/// junctions as a bitmask.
+struct LongestPath<'a, N> {
+    graph: &'a JunctionGraph<N>,
+    start: usize,
+    end: usize,
+    /// Once here the only way on is to the end, so any other move is a dead
+    /// end.
+    last_before_end: Option<usize>,
+}
+
+impl<'a, N> LongestPath<'a, N>
+where
+    N: Eq + Hash,
+{
+    fn new(graph: &'a JunctionGraph<N>, start: &N, end: &N) -> Option<Self> {
+        assert!(
+            graph.len() <= 64,
+            "too many junctions ({}) for a bitmask search",
+            graph.len()
+        );
+        let start = graph.index_of(start)?;
+        let end = graph.index_of(end)?;
+        let mut into_end =
+            (0..graph.len()).filter(|n| graph.edges[*n].iter().any(|(to, _)| *to == end));
+        let last_before_end = match (into_end.next(), into_end.next()) {
+            (Some(n), None) => Some(n),
+            _ => None,
+        };
+        Some(Self {
+            graph,
+            start,
+            end,
+            last_before_end,
+        })
+    }
+
+    fn moves(&self, node: usize, visited: u64) -> impl Iterator<Item = (usize, u64)> + '_ {
+        let forced = self.last_before_end == Some(node);
+        self.graph.edges[node]
+            .iter()
+            .filter(move |(to, _)| visited & 1 << to == 0 && (!forced || *to == self.end))
+            .copied()
+    }
+
+    /// The longest way on to the end from `node`, having visited `visited`.
+    fn longest(
+        &self,
+        node: usize,
+        visited: u64,
+        memo: &mut HashMap<(usize, u64), Option<u64>>,
+    ) -> Option<u64> {
+        if node == self.end {
+            return Some(0);
+        }
+        if let Some(known) = memo.get(&(node, visited)) {
+            return *known;
+        }
+        let best = self
+            .moves(node, visited)
+            .filter_map(|(to, weight)| Some(weight + self.longest(to, visited | 1 << to, memo)?))
+            .max();
+        memo.insert((node, visited), best);
+        best
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MAZE: [&str; 7] = [
+        "#.#####", "#.....#", "#.###.#", "#.....#", "#.#.#.#", "#.....#", "#####.#",
+    ];
+
+    fn open(x: isize, y: isize) -> bool {
+        (0..7).contains(&x)
+            && (0..7).contains(&y)
+            && MAZE[y as usize].as_bytes()[x as usize] == b'.'
+    }
+
+    fn neighbours(&(x, y): &(isize, isize)) -> Vec<(isize, isize)> {
+        [(0, 1), (1, 0), (0, -1), (-1, 0)]
+            .into_iter()
+            .map(|(dx, dy)| (x + dx, y + dy))
+            .filter(|(x, y)| open(*x, *y))
+            .collect()
+    }
+
+    #[test]
+    fn contract() {
+        let graph = JunctionGraph::contract(&[(1, 0), (5, 6)], neighbours);
+        assert_eq!(Some(0), graph.index_of(&(1, 0)));
+        assert_eq!(Some(1), graph.index_of(&(5, 6)));
+        assert!(graph.index_of(&(1, 2)).is_none());
+        let junction = graph.index_of(&(1, 1)).unwrap();
+        assert_eq!(&[(junction, 1)], graph.edges(0));
+        assert_eq!(3, graph.edges(junction).len());
+        assert_eq!(8, graph.len());
+    }
+
+    #[test]
+    fn longest_path() {
+        let graph = JunctionGraph::contract(&[(1, 0), (5, 6)], neighbours);
+        assert_eq!(Some(18), graph.longest_path(&(1, 0), &(5, 6)));
+        assert_eq!(Some(18), graph.longest_path_parallel(&(1, 0), &(5, 6), 3));
+        assert_eq!(Some(17), graph.longest_path(&(1, 0), &(3, 3)));
+        assert_eq!(None, graph.longest_path(&(1, 0), &(1, 2)));
+
+        // Corridors which only run one way
+        let one_way = |node: &(isize, isize)| {
+            neighbours(node)
+                .into_iter()
+                .filter(|next| next.0 >= node.0)
+                .collect()
+        };
+        let graph = JunctionGraph::contract(&[(1, 0), (5, 6)], one_way);
+        assert_eq!(Some(14), graph.longest_path(&(1, 0), &(5, 6)));
+    }
+}
@@ -0,0 +1,386 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use tracing::debug;
+
+/// An undirected graph whose nodes are labelled by name, where every edge
+/// has unit weight and parallel edges are allowed.
+#[derive(Debug, Clone, Default)]
+pub struct UndirectedGraph {
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+    edges: Vec<(usize, usize)>,
+    /// For each node, its neighbours and the id of the edge to each.
+    adjacency: Vec<Vec<(usize, usize)>>,
+}
+
+/// A partition of an [`UndirectedGraph`] into two, as found by
+/// [`UndirectedGraph::stoer_wagner`] or [`UndirectedGraph::karger`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut<'a> {
+    /// The number of edges crossing between the two sides.
+    pub weight: u64,
+    /// The nodes on one side; every other node is on the other.
+    pub partition: Vec<&'a str>,
+}
+
+impl UndirectedGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The index of the node called `name`, adding it if it is new.
+    pub fn add_node(&mut self, name: &str) -> usize {
+        if let Some(index) = self.index.get(name) {
+            return *index;
+        }
+        self.index.insert(name.to_string(), self.names.len());
+        self.names.push(name.to_string());
+        self.adjacency.push(Vec::new());
+        self.names.len() - 1
+    }
+
+    /// Join `a` and `b`, adding either if it is new.
+    pub fn add_edge(&mut self, a: &str, b: &str) {
+        let (a, b) = (self.add_node(a), self.add_node(b));
+        let id = self.edges.len();
+        self.edges.push((a, b));
+        self.adjacency[a].push((b, id));
+        if a != b {
+            self.adjacency[b].push((a, id));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// The name of the node at `index`.
+    pub fn name(&self, index: usize) -> &str {
+        &self.names[index]
+    }
+
+    /// The index of the node called `name`, if there is one.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    /// The names of the nodes joined to `name`, once for each edge.
+    pub fn neighbours(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.index_of(name)
+            .into_iter()
+            .flat_map(|index| &self.adjacency[index])
+            .map(|(neighbour, _)| self.name(*neighbour))
+    }
+
+    /// The names in each connected component, in order of the first node
+    /// added to each.
+    pub fn connected_components(&self) -> Vec<Vec<&str>> {
+        let mut seen = vec![false; self.len()];
+        let mut components = Vec::new();
+        for root in 0..self.len() {
+            if seen[root] {
+                continue;
+            }
+            seen[root] = true;
+            let mut component = Vec::new();
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                component.push(self.name(node));
+                for (neighbour, _) in &self.adjacency[node] {
+                    if !seen[*neighbour] {
+                        seen[*neighbour] = true;
+                        stack.push(*neighbour);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// The edges whose removal would disconnect their two ends.
+    pub fn bridges(&self) -> Vec<(&str, &str)> {
+        // Iterative Tarjan: discovery order and the lowest order reachable
+        // from each subtree without reusing the edge in from its parent
+        let mut order = vec![usize::MAX; self.len()];
+        let mut low = vec![0; self.len()];
+        let mut bridges = Vec::new();
+        let mut counter = 0;
+        for root in 0..self.len() {
+            if order[root] != usize::MAX {
+                continue;
+            }
+            order[root] = counter;
+            low[root] = counter;
+            counter += 1;
+            // (node, edge in from the parent, next adjacency entry to visit)
+            let mut stack = vec![(root, usize::MAX, 0)];
+            while let Some((node, parent_edge, next)) = stack.last_mut() {
+                let node = *node;
+                if let Some((neighbour, id)) = self.adjacency[node].get(*next).copied() {
+                    *next += 1;
+                    if id == *parent_edge {
+                        continue;
+                    }
+                    if order[neighbour] == usize::MAX {
+                        order[neighbour] = counter;
+                        low[neighbour] = counter;
+                        counter += 1;
+                        stack.push((neighbour, id, 0));
+                    } else {
+                        low[node] = low[node].min(order[neighbour]);
+                    }
+                } else {
+                    let parent_edge = *parent_edge;
+                    stack.pop();
+                    if let Some((parent, _, _)) = stack.last() {
+                        low[*parent] = low[*parent].min(low[node]);
+                        if low[node] > order[*parent] {
+                            let (a, b) = self.edges[parent_edge];
+                            bridges.push((self.name(a), self.name(b)));
+                        }
+                    }
+                }
+            }
+        }
+        bridges
+    }
+
+    /// The minimum cut, found deterministically by Stoer–Wagner, or `None` if
+    /// there are fewer than two nodes.
+    ///
+    /// Where several cuts of the same weight are seen, the one splitting the
+    /// nodes most evenly is kept, so a lone node whose degree happens to
+    /// equal the minimum cut is not preferred.
+    pub fn stoer_wagner(&self) -> Option<Cut<'_>> {
+        if self.len() < 2 {
+            return None;
+        }
+        // Merged nodes keep the summed weight of their edges to each other node
+        let mut weights = vec![HashMap::new(); self.len()];
+        for (a, b) in &self.edges {
+            if a != b {
+                *weights[*a].entry(*b).or_insert(0) += 1;
+                *weights[*b].entry(*a).or_insert(0) += 1;
+            }
+        }
+        let mut members = (0..self.len()).map(|n| vec![n]).collect::<Vec<_>>();
+        let mut alive = (0..self.len()).collect::<Vec<_>>();
+        let mut best: Option<(u64, Vec<usize>)> = None;
+
+        while alive.len() > 1 {
+            // Add nodes in order of how tightly they connect to those added
+            let mut connection = vec![0u64; self.len()];
+            let mut added = vec![false; self.len()];
+            let mut queue = BinaryHeap::from([(0, alive[0])]);
+            let (mut previous, mut last, mut count) = (alive[0], alive[0], 0);
+            while let Some((weight, node)) = queue.pop() {
+                if added[node] || weight != connection[node] {
+                    continue;
+                }
+                added[node] = true;
+                (previous, last) = (last, node);
+                count += 1;
+                for (neighbour, w) in &weights[node] {
+                    if !added[*neighbour] {
+                        connection[*neighbour] += w;
+                        queue.push((connection[*neighbour], *neighbour));
+                    }
+                }
+            }
+            if count < alive.len() {
+                // Disconnected, so whatever was reached is cut off for free
+                let partition = alive
+                    .iter()
+                    .filter(|n| added[**n])
+                    .flat_map(|n| members[*n].iter().copied())
+                    .collect();
+                best = Some((0, partition));
+                break;
+            }
+            // Among equal cuts prefer the most even split
+            let balance = |size: usize| size.min(self.len() - size);
+            if best.as_ref().is_none_or(|(w, side)| {
+                (connection[last], Reverse(balance(members[last].len())))
+                    < (*w, Reverse(balance(side.len())))
+            }) {
+                best = Some((connection[last], members[last].clone()));
+            }
+
+            // Merge the last node added into the one before it
+            for (neighbour, w) in std::mem::take(&mut weights[last]) {
+                weights[neighbour].remove(&last);
+                if neighbour != previous {
+                    *weights[previous].entry(neighbour).or_insert(0) += w;
+                    *weights[neighbour].entry(previous).or_insert(0) += w;
+                }
+            }
+            let merged = std::mem::take(&mut members[last]);
+            members[previous].extend(merged);
+            alive.retain(|n| *n != last);
+        }
+        let (weight, partition) = best?;
+        debug!(weight, size = partition.len(), "stoer-wagner");
+        Some(Cut {
+            weight,
+            partition: partition.into_iter().map(|n| self.name(n)).collect(),
+        })
+    }
+
+    /// The smallest cut found by `attempts` rounds of Karger's random
+    /// contraction, or `None` if there are fewer than two nodes.
+    ///
+    /// Each round finds a minimum cut with probability at least
+    /// `2 / n(n - 1)`; `seed` makes the rounds repeatable.
+    pub fn karger(&self, attempts: usize, seed: u64) -> Option<Cut<'_>> {
+        if self.len() < 2 {
+            return None;
+        }
+        let mut rng = XorShift(seed.max(1));
+        let mut best: Option<(u64, Vec<usize>)> = None;
+        let mut edges = self.edges.clone();
+        for _ in 0..attempts {
+            // Contracting edges in a random order is a shuffle then union-find
+            for i in (1..edges.len()).rev() {
+                let j = (rng.next() % (i as u64 + 1)) as usize;
+                edges.swap(i, j);
+            }
+            let mut sets = DisjointSet::new(self.len());
+            let mut remaining = self.len();
+            for (a, b) in &edges {
+                if remaining == 2 {
+                    break;
+                }
+                if sets.union(*a, *b) {
+                    remaining -= 1;
+                }
+            }
+            let weight = self
+                .edges
+                .iter()
+                .filter(|(a, b)| sets.find(*a) != sets.find(*b))
+                .count() as u64;
+            if best.as_ref().is_none_or(|(w, _)| weight < *w) {
+                let side = sets.find(0);
+                let partition = (0..self.len()).filter(|n| sets.find(*n) == side).collect();
+                best = Some((weight, partition));
+            }
+        }
+        let (weight, partition) = best?;
+        debug!(weight, size = partition.len(), attempts, "karger");
+        Some(Cut {
+            weight,
+            partition: partition.into_iter().map(|n| self.name(n)).collect(),
+        })
+    }
+}
+
+/// A small xorshift generator, which is plenty for shuffling edges.
+struct XorShift(u64);
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+impl DisjointSet {
+    fn new(size: usize) -> Self {
+        Self {
+            parent: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, mut node: usize) -> usize {
+        while self.parent[node] != node {
+            self.parent[node] = self.parent[self.parent[node]];
+            node = self.parent[node];
+        }
+        node
+    }
+
+    /// Join the sets holding `a` and `b`, returning whether they were apart.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[a] = b;
+        a != b
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Two squares with both diagonals, joined by two edges.
+    fn dumbbell() -> UndirectedGraph {
+        let mut graph = UndirectedGraph::new();
+        for side in ["a", "b"] {
+            let names = (0..4).map(|n| format!("{side}{n}")).collect::<Vec<_>>();
+            for i in 0..4 {
+                for j in i + 1..4 {
+                    graph.add_edge(&names[i], &names[j]);
+                }
+            }
+        }
+        graph.add_edge("a0", "b0");
+        graph.add_edge("a1", "b1");
+        graph
+    }
+
+    fn sorted(mut names: Vec<&str>) -> Vec<&str> {
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn components_and_bridges() {
+        let mut graph = dumbbell();
+        assert_eq!(1, graph.connected_components().len());
+        assert!(graph.bridges().is_empty());
+
+        graph.add_edge("b3", "c");
+        graph.add_edge("x", "y");
+        graph.add_edge("x", "y");
+        assert_eq!(vec![("b3", "c")], graph.bridges());
+        let components = graph.connected_components();
+        assert_eq!(2, components.len());
+        assert_eq!(vec!["x", "y"], sorted(components[1].clone()));
+        assert_eq!(vec!["x", "x"], graph.neighbours("y").collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn minimum_cut() {
+        let graph = dumbbell();
+        let cut = graph.stoer_wagner().unwrap();
+        assert_eq!(2, cut.weight);
+        let side = if cut.partition.contains(&"a0") {
+            vec!["a0", "a1", "a2", "a3"]
+        } else {
+            vec!["b0", "b1", "b2", "b3"]
+        };
+        assert_eq!(side, sorted(cut.partition));
+
+        let cut = graph.karger(50, 1).unwrap();
+        assert_eq!(2, cut.weight);
+        assert_eq!(4, cut.partition.len());
+
+        let mut graph = UndirectedGraph::new();
+        graph.add_node("a");
+        assert!(graph.stoer_wagner().is_none());
+        graph.add_node("b");
+        assert_eq!(0, graph.stoer_wagner().unwrap().weight);
+    }
+}
@@ -4,11 +4,15 @@ use std::{
     hash::Hash,
     iter::Step,
     ops::{Add, AddAssign, RangeInclusive, Sub},
+    str::FromStr,
 };
 
-use crate::Point;
+use crate::{
+    parse::{Line, ParseError},
+    Point,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Range<T> {
     pub x: RangeInclusive<T>,
     pub y: RangeInclusive<T>,
@@ -25,8 +29,11 @@ where
     }
 }
 
+/// A sparse grid of cells keyed by their [`Point`], tracking the bounds of
+/// every cell set so far. Puzzles whose input fills a rectangle should use
+/// [`DenseMatrix`](crate::DenseMatrix) instead.
 #[derive(Debug, Clone)]
-struct Grid<T, V>
+pub struct Grid<T, V>
 where
     V: Default
         + Sized
@@ -104,6 +111,10 @@ where
         self.data.get(point)
     }
 
+    pub fn get_mut(&mut self, point: &Point<V>) -> Option<&mut T> {
+        self.data.get_mut(point)
+    }
+
     pub fn dimensions(&self) -> &Range<V> {
         &self.range
     }
@@ -124,6 +135,11 @@ where
         self.range.y.end()
     }
 
+    /// Every cell which has been set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Point<V>, &T)> {
+        self.data.iter()
+    }
+
     pub fn display(&self)
     where
         V: Display + Step,
@@ -150,3 +166,205 @@ where
         }
     }
 }
+
+impl<T, V> Grid<T, V>
+where
+    T: Default + Display + Clone,
+    V: Default
+        + Step
+        + Ord
+        + Sized
+        + Copy
+        + Sub<Output = V>
+        + Add<Output = V>
+        + AddAssign
+        + Eq
+        + PartialEq
+        + Hash,
+{
+    /// Set the cell at `point`, growing the bounds to include it, and return
+    /// the value it replaced.
+    pub fn set(&mut self, point: Point<V>, value: T) -> Option<T> {
+        let (x, y) = (point.x(), point.y());
+        if self.data.is_empty() {
+            self.range = Range { x: x..=x, y: y..=y };
+        } else {
+            self.range.x = *self.range.x.start().min(&x)..=*self.range.x.end().max(&x);
+            self.range.y = *self.range.y.start().min(&y)..=*self.range.y.end().max(&y);
+        }
+        self.data.insert(point, value)
+    }
+
+    /// Whether `point` lies within the bounds of the grid.
+    pub fn contains(&self, point: &Point<V>) -> bool {
+        self.range.x.contains(&point.x()) && self.range.y.contains(&point.y())
+    }
+
+    /// The cells of row `y` which have been set, from left to right.
+    pub fn row(&self, y: V) -> impl Iterator<Item = (Point<V>, &T)> {
+        self.range.x.clone().filter_map(move |x| {
+            let point = Point::new(x, y);
+            self.get(&point).map(|value| (point, value))
+        })
+    }
+
+    /// The cells of column `x` which have been set, from top to bottom.
+    pub fn column(&self, x: V) -> impl Iterator<Item = (Point<V>, &T)> {
+        self.range.y.clone().filter_map(move |y| {
+            let point = Point::new(x, y);
+            self.get(&point).map(|value| (point, value))
+        })
+    }
+
+    /// The cells set in the four cardinal directions from `point`.
+    pub fn cardinal_neighbours(&self, point: &Point<V>) -> impl Iterator<Item = (Point<V>, &T)> {
+        self.offsets(point, &[(0, -1), (1, 0), (0, 1), (-1, 0)])
+    }
+
+    /// The cells set in all eight directions from `point`.
+    pub fn neighbours(&self, point: &Point<V>) -> impl Iterator<Item = (Point<V>, &T)> {
+        self.offsets(
+            point,
+            &[
+                (0, -1),
+                (1, 0),
+                (0, 1),
+                (-1, 0),
+                (1, -1),
+                (1, 1),
+                (-1, 1),
+                (-1, -1),
+            ],
+        )
+    }
+
+    fn offsets(
+        &self,
+        point: &Point<V>,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (Point<V>, &T)> {
+        let step = |v: V, d: isize| match d {
+            -1 => V::backward_checked(v, 1),
+            1 => V::forward_checked(v, 1),
+            _ => Some(v),
+        };
+        let (x, y) = (point.x(), point.y());
+        offsets
+            .iter()
+            .filter_map(move |(dx, dy)| Some(Point::new(step(x, *dx)?, step(y, *dy)?)))
+            .filter_map(|point| self.get(&point).map(|value| (point, value)))
+    }
+}
+
+/// Read a block of characters, one row per line, with `(0, 0)` at the top
+/// left.
+impl<T, V> FromStr for Grid<T, V>
+where
+    T: TryFrom<char> + Default + Display + Clone,
+    V: Default
+        + Step
+        + Ord
+        + Sized
+        + Copy
+        + Sub<Output = V>
+        + Add<Output = V>
+        + AddAssign
+        + Eq
+        + PartialEq
+        + Hash,
+{
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut grid = Self::new();
+        for (y, text) in s.lines().enumerate() {
+            let line = Line::new(y + 1, text);
+            for (x, (i, c)) in line.text.char_indices().enumerate() {
+                let value = T::try_from(c)
+                    .map_err(|_| line.error(&line.text[i..i + c.len_utf8()], "a grid cell"))?;
+                let point = Point::new(V::forward(V::default(), x), V::forward(V::default(), y));
+                grid.set(point, value);
+            }
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn set_and_bounds() {
+        let mut grid = Grid::<char, isize>::new();
+        assert!(grid.is_empty());
+        assert_eq!(None, grid.set(Point::new(3, 4), 'a'));
+        assert_eq!(&Range { x: 3..=3, y: 4..=4 }, grid.dimensions());
+        grid.set(Point::new(-1, 6), 'b');
+        assert_eq!(Some('b'), grid.set(Point::new(-1, 6), 'c'));
+        assert_eq!(
+            &Range {
+                x: -1..=3,
+                y: 4..=6
+            },
+            grid.dimensions()
+        );
+        assert_eq!(2, grid.len());
+        assert!(grid.contains(&Point::new(0, 5)));
+        assert!(!grid.contains(&Point::new(0, 7)));
+    }
+
+    #[test]
+    fn from_str() {
+        let grid = "#.\n.#\n##".parse::<Grid<char, usize>>().unwrap();
+        assert_eq!(Some(&'#'), grid.get(&Point::new(1, 2)));
+        assert_eq!((&1, &2), (grid.max_x(), grid.max_y()));
+        assert_eq!(
+            vec!['#', '.', '#'],
+            grid.column(0).map(|(_, c)| *c).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Point::new(0, 1), Point::new(1, 1)],
+            grid.row(1).map(|(p, _)| p).collect::<Vec<_>>()
+        );
+
+        let e = "12\n3x".parse::<Grid<Digit, usize>>().unwrap_err();
+        assert!(matches!(
+            e,
+            ParseError::Syntax {
+                line: 2,
+                column: 2,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = "abc\ndef\nghi".parse::<Grid<char, usize>>().unwrap();
+        let cardinal = |p| {
+            grid.cardinal_neighbours(&p)
+                .map(|(_, c)| *c)
+                .collect::<String>()
+        };
+        assert_eq!("bd", cardinal(Point::new(0, 0)));
+        assert_eq!("bfhd", cardinal(Point::new(1, 1)));
+        assert_eq!(8, grid.neighbours(&Point::new(1, 1)).count());
+        assert_eq!(3, grid.neighbours(&Point::new(2, 2)).count());
+    }
+
+    #[derive(Debug, Default, Clone)]
+    struct Digit(u32);
+    impl Display for Digit {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+    impl TryFrom<char> for Digit {
+        type Error = ();
+
+        fn try_from(c: char) -> Result<Self, Self::Error> {
+            c.to_digit(10).map(Digit).ok_or(())
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! Ranges of values, sets of them, and boxes made of one range along each
+//! of several axes, which can be cut up and combined without enumerating
+//! the values inside.
+use std::{
+    iter::Step,
+    ops::{Add, Mul, Range, Sub},
+};
+
+mod piecewise;
+
+pub use piecewise::PiecewiseShift;
+
+/// The values from `start` up to but not including `end`, which is empty
+/// unless `start < end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Copy + Ord> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.start <= *value && *value < self.end
+    }
+
+    /// The values in both, which may be empty.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        }
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The values below `at`, and the rest, either of which may be empty.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let at = at.clamp(self.start, self.end.max(self.start));
+        (
+            Self {
+                start: self.start,
+                end: at,
+            },
+            Self {
+                start: at,
+                end: self.end,
+            },
+        )
+    }
+}
+
+impl<T: Copy + Ord + Step> Interval<T> {
+    /// The values from `first` to `last`, including both.
+    pub fn inclusive(first: T, last: T) -> Self {
+        Self {
+            start: first,
+            end: T::forward(last, 1),
+        }
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T>> Interval<T> {
+    /// The `len` values from `start`.
+    pub fn with_len(start: T, len: T) -> Self {
+        Self {
+            start,
+            end: start + len,
+        }
+    }
+
+    /// Every value moved along by `offset`.
+    pub fn shift(&self, offset: T) -> Self {
+        Self {
+            start: self.start + offset,
+            end: self.end + offset,
+        }
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Default> Interval<T> {
+    /// The number of values, which is zero (the default) if empty.
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::default()
+        } else {
+            self.end - self.start
+        }
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+/// A set of values held as the disjoint [`Interval`]s covering them, in
+/// order, with no two touching.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The set of the values in any of `intervals`, which may overlap.
+    fn normalised(mut intervals: Vec<Interval<T>>) -> Self {
+        intervals.retain(|interval| !interval.is_empty());
+        intervals.sort();
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        Self { intervals: merged }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.intervals
+            .binary_search_by(|interval| {
+                if interval.end <= *value {
+                    std::cmp::Ordering::Less
+                } else if interval.start > *value {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// The smallest value in the set.
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(Interval::start)
+    }
+
+    /// Add every value in `interval`.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = Self::normalised(intervals);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalised(self.iter().chain(other.iter()).copied().collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            let both = a.intersection(&b);
+            if !both.is_empty() {
+                intervals.push(both);
+            }
+            // Move past whichever finishes first
+            if a.end <= b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals }
+    }
+
+    /// The values in `self` but not `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut j = 0;
+        for interval in &self.intervals {
+            let mut rest = *interval;
+            // Skip those of `other` which end before this starts
+            while j < other.intervals.len() && other.intervals[j].end <= rest.start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.intervals.len() && other.intervals[k].start < rest.end {
+                let (before, _) = rest.split_at(other.intervals[k].start);
+                if !before.is_empty() {
+                    intervals.push(before);
+                }
+                rest = rest.split_at(other.intervals[k].end).1;
+                k += 1;
+            }
+            if !rest.is_empty() {
+                intervals.push(rest);
+            }
+        }
+        Self { intervals }
+    }
+
+    /// The values below `at`, and the rest.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let (mut below, mut above) = (Vec::new(), Vec::new());
+        for interval in &self.intervals {
+            let (low, high) = interval.split_at(at);
+            if !low.is_empty() {
+                below.push(low);
+            }
+            if !high.is_empty() {
+                above.push(high);
+            }
+        }
+        (Self { intervals: below }, Self { intervals: above })
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Add<Output = T> + Default> IntervalSet<T> {
+    /// The number of values in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::default(), |total, interval| total + interval.len())
+    }
+}
+
+impl<T: Copy + Ord> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        Self::normalised(vec![interval])
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        Self::normalised(iter.into_iter().collect())
+    }
+}
+
+/// A box in `N` dimensions: the points whose coordinate along each axis is
+/// within the [`Interval`] for that axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HyperRect<T, const N: usize> {
+    sides: [Interval<T>; N],
+}
+
+impl<T: Copy + Ord, const N: usize> HyperRect<T, N> {
+    pub fn new(sides: [Interval<T>; N]) -> Self {
+        Self { sides }
+    }
+
+    pub fn sides(&self) -> &[Interval<T>; N] {
+        &self.sides
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sides.iter().any(Interval::is_empty)
+    }
+
+    pub fn contains(&self, point: &[T; N]) -> bool {
+        self.sides
+            .iter()
+            .zip(point)
+            .all(|(side, value)| side.contains(value))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            sides: std::array::from_fn(|axis| self.sides[axis].intersection(&other.sides[axis])),
+        }
+    }
+
+    /// The part with coordinates below `at` along `axis`, and the rest.
+    pub fn split_at(&self, axis: usize, at: T) -> (Self, Self) {
+        let (low, high) = self.sides[axis].split_at(at);
+        let (mut below, mut above) = (*self, *self);
+        below.sides[axis] = low;
+        above.sides[axis] = high;
+        (below, above)
+    }
+}
+
+impl<T, const N: usize> HyperRect<T, N>
+where
+    T: Copy + Ord + Sub<Output = T> + Mul<Output = T> + Default + From<u8>,
+{
+    /// The number of points inside.
+    pub fn volume(&self) -> T {
+        self.sides
+            .iter()
+            .fold(T::from(1), |volume, side| volume * side.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    #[test]
+    fn intervals() {
+        let a = Interval::from(2..8);
+        assert_eq!(6, a.len());
+        assert_eq!(Interval::inclusive(2, 7), a);
+        assert_eq!(Interval::with_len(2, 6), a);
+        assert!(a.contains(&7) && !a.contains(&8));
+        assert_eq!(Interval::new(5, 8), a.intersection(&Interval::new(5, 10)));
+        assert!(!a.overlaps(&Interval::new(8, 10)));
+        assert_eq!(0, Interval::new(5, 3).len());
+
+        assert_eq!((Interval::new(2, 4), Interval::new(4, 8)), a.split_at(4));
+        let (below, above) = a.split_at(0);
+        assert!(below.is_empty());
+        assert_eq!(a, above);
+        assert_eq!(Interval::new(-1, 5), a.shift(-3));
+    }
+
+    #[test]
+    fn sets() {
+        // Overlapping and touching intervals merge
+        let a = set(&[(5, 8), (0, 2), (1, 3), (8, 10), (12, 12)]);
+        assert_eq!(set(&[(0, 3), (5, 10)]).intervals(), a.intervals());
+        assert_eq!(8, a.len());
+        assert!(a.contains(&9) && !a.contains(&4) && !a.contains(&10));
+        assert_eq!(Some(0), a.min());
+
+        let b = set(&[(2, 6), (9, 20)]);
+        assert_eq!(set(&[(0, 20)]), a.union(&b));
+        assert_eq!(set(&[(2, 3), (5, 6), (9, 10)]), a.intersection(&b));
+        assert_eq!(set(&[(0, 2), (6, 9)]), a.difference(&b));
+        assert_eq!(set(&[(3, 5), (10, 20)]), b.difference(&a));
+
+        let (below, above) = a.split_at(6);
+        assert_eq!(set(&[(0, 3), (5, 6)]), below);
+        assert_eq!(set(&[(6, 10)]), above);
+
+        let mut c = IntervalSet::new();
+        c.insert(Interval::new(4, 5));
+        c.insert(Interval::new(3, 4));
+        assert_eq!(set(&[(3, 5)]), c);
+    }
+
+    #[test]
+    fn hyper_rects() {
+        let cube = HyperRect::new([Interval::inclusive(1u64, 4000); 4]);
+        assert_eq!(4000u64.pow(4), cube.volume());
+
+        let (below, above) = cube.split_at(2, 1001);
+        assert_eq!(1000 * 4000u64.pow(3), below.volume());
+        assert_eq!(3000 * 4000u64.pow(3), above.volume());
+        assert!(above.contains(&[1, 4000, 1001, 1]));
+        assert!(!above.contains(&[1, 4000, 1000, 1]));
+        assert!(below.intersection(&above).is_empty());
+        assert_eq!(0, below.intersection(&above).volume());
+    }
+}
@@ -0,0 +1,177 @@
+use std::ops::{Add, Sub};
+
+use super::{Interval, IntervalSet};
+
+/// A function which adds a fixed offset to the values in each of a number
+/// of disjoint intervals, and leaves all other values as they are: a
+/// piecewise linear function whose every piece has slope one.
+///
+/// These are closed under composition, so a whole chain of them can be
+/// collapsed into one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecewiseShift<T> {
+    /// In order, with no zero offsets.
+    pieces: Vec<(Interval<T>, T)>,
+}
+
+impl<T> Default for PiecewiseShift<T> {
+    fn default() -> Self {
+        Self { pieces: Vec::new() }
+    }
+}
+
+impl<T> PiecewiseShift<T>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Default,
+{
+    /// The identity function.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The function adding each offset to the values in its interval, where
+    /// the first interval listed wins if any overlap.
+    pub fn from_pieces(pieces: impl IntoIterator<Item = (Interval<T>, T)>) -> Self {
+        let mut covered = IntervalSet::new();
+        let mut disjoint = Vec::new();
+        for (interval, offset) in pieces {
+            for part in IntervalSet::from(interval).difference(&covered).iter() {
+                disjoint.push((*part, offset));
+            }
+            covered.insert(interval);
+        }
+        Self::sorted(disjoint)
+    }
+
+    fn sorted(mut pieces: Vec<(Interval<T>, T)>) -> Self {
+        pieces.retain(|(interval, offset)| !interval.is_empty() && *offset != T::default());
+        pieces.sort();
+        Self { pieces }
+    }
+
+    /// The intervals with their offsets, in order; values outside them are
+    /// left unchanged.
+    pub fn pieces(&self) -> &[(Interval<T>, T)] {
+        &self.pieces
+    }
+
+    /// The values which this moves.
+    fn domain(&self) -> IntervalSet<T> {
+        self.pieces.iter().map(|(interval, _)| *interval).collect()
+    }
+
+    pub fn apply(&self, value: T) -> T {
+        let index = self
+            .pieces
+            .partition_point(|(interval, _)| interval.end() <= value);
+        match self.pieces.get(index) {
+            Some((interval, offset)) if interval.contains(&value) => value + *offset,
+            _ => value,
+        }
+    }
+
+    /// The function applying `self` and then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        let next_domain = next.domain();
+        let mut pieces = Vec::new();
+        for (interval, offset) in &self.pieces {
+            // Where this piece sends its values, cut up by `next`'s pieces
+            let image = IntervalSet::from(interval.shift(*offset));
+            let back =
+                |part: &Interval<T>| Interval::new(part.start() - *offset, part.end() - *offset);
+            for (next_interval, next_offset) in &next.pieces {
+                for part in image
+                    .intersection(&IntervalSet::from(*next_interval))
+                    .iter()
+                {
+                    pieces.push((back(part), *offset + *next_offset));
+                }
+            }
+            for part in image.difference(&next_domain).iter() {
+                pieces.push((back(part), *offset));
+            }
+        }
+        // Values `self` leaves alone go straight to `next`
+        let domain = self.domain();
+        for (interval, offset) in &next.pieces {
+            for part in IntervalSet::from(*interval).difference(&domain).iter() {
+                pieces.push((*part, *offset));
+            }
+        }
+        Self::sorted(pieces)
+    }
+
+    /// Every value which `values` are sent to.
+    pub fn image(&self, values: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut image = values.difference(&self.domain());
+        for (interval, offset) in &self.pieces {
+            for part in values.intersection(&IntervalSet::from(*interval)).iter() {
+                image.insert(part.shift(*offset));
+            }
+        }
+        image
+    }
+
+    /// The smallest value which any of `values` is sent to, found from the
+    /// lowest value in each piece rather than the whole image.
+    pub fn min_over(&self, values: &IntervalSet<T>) -> Option<T> {
+        self.pieces
+            .iter()
+            .filter_map(|(interval, offset)| {
+                let lowest = values.intersection(&IntervalSet::from(*interval)).min()?;
+                Some(lowest + *offset)
+            })
+            .chain(values.difference(&self.domain()).min())
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shifts() {
+        // The first two stages of the almanac example
+        let soil = PiecewiseShift::from_pieces([
+            (Interval::new(98, 100), -48),
+            (Interval::new(50, 98), 2),
+        ]);
+        let fertilizer = PiecewiseShift::from_pieces([
+            (Interval::new(15, 52), -15),
+            (Interval::new(52, 54), -15),
+            (Interval::new(0, 15), 39),
+        ]);
+        assert_eq!(81, soil.apply(79));
+        assert_eq!(14, soil.apply(14));
+        assert_eq!(50, soil.apply(98));
+
+        let both = soil.then(&fertilizer);
+        for seed in -5..110 {
+            assert_eq!(fertilizer.apply(soil.apply(seed)), both.apply(seed));
+        }
+
+        let seeds = IntervalSet::from_iter([Interval::new(79, 93), Interval::new(55, 68)]);
+        let image = both.image(&seeds);
+        let mut expected = seeds
+            .iter()
+            .flat_map(|interval| interval.start()..interval.end())
+            .map(|seed| both.apply(seed))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(expected.len() as i64, image.len());
+        assert!(expected.iter().all(|value| image.contains(value)));
+        assert_eq!(Some(expected[0]), both.min_over(&seeds));
+        assert_eq!(image.min(), both.min_over(&seeds));
+
+        // The first piece listed wins where they overlap
+        let overlapping =
+            PiecewiseShift::from_pieces([(Interval::new(0, 10), 5), (Interval::new(5, 15), 100)]);
+        assert_eq!(14, overlapping.apply(9));
+        assert_eq!(110, overlapping.apply(10));
+        assert_eq!(
+            PiecewiseShift::new(),
+            PiecewiseShift::from_pieces([(Interval::new(1, 2), 0)])
+        );
+    }
+}
@@ -1,16 +1,36 @@
 #![feature(step_trait)]
+mod answer;
+mod context;
+mod cuboid;
+pub mod cycle;
+mod dense_matrix;
+pub mod geometry;
 pub mod graph;
+pub mod grid;
+pub mod interval;
+pub mod linalg;
 pub mod math;
 mod matrix;
+pub mod parse;
 mod point;
+mod point3;
 mod runner;
+mod sidecar;
 mod solution;
+mod timing;
 
-//pub use grid::Grid;
+pub use answer::Answer;
+pub use context::RunContext;
+pub use cuboid::Cuboid;
+pub use dense_matrix::{DenseMatrix, View};
+pub use grid::Grid;
+#[allow(deprecated)]
 pub use matrix::Matrix;
 pub use point::Point;
-pub use runner::{log_init, run, BaseName};
+pub use point3::Point3;
+pub use runner::{log_init, run, Answers, Expected, Report, Verdict};
 pub use solution::{load, Solution};
+pub use timing::{Phase, Timing, Timings};
 
 #[macro_export]
 macro_rules! map(
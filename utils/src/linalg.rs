@@ -0,0 +1,271 @@
+//! Solving systems of linear equations exactly, either over the rationals
+//! or, for systems whose solution is whole numbers, over the integers.
+use std::fmt::Display;
+
+use crate::math::{modular_inverse, Rational};
+
+/// Why a system of equations has no single solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveError {
+    /// There are too few independent equations, leaving `free` unknowns
+    /// which could take any value.
+    Underdetermined { free: usize },
+    /// The equations contradict each other.
+    Inconsistent,
+    /// The solution is not all whole numbers.
+    NotIntegral,
+    /// An intermediate value is too big for an `i128`.
+    Overflow,
+}
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Underdetermined { free } => {
+                write!(f, "too few independent equations, {free} unknown(s) free")
+            }
+            Self::Inconsistent => write!(f, "the equations contradict each other"),
+            Self::NotIntegral => write!(f, "no solution in whole numbers"),
+            Self::Overflow => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+impl std::error::Error for SolveError {}
+
+/// The arithmetic which elimination needs.
+trait Field: Copy + PartialEq {
+    fn is_zero(&self) -> bool;
+    /// `self - a * b`
+    fn sub_mul(self, a: Self, b: Self) -> Option<Self>;
+    fn div(self, rhs: Self) -> Option<Self>;
+}
+
+impl Field for Rational {
+    fn is_zero(&self) -> bool {
+        Rational::is_zero(self)
+    }
+
+    fn sub_mul(self, a: Self, b: Self) -> Option<Self> {
+        self.checked_sub(a.checked_mul(b)?)
+    }
+
+    fn div(self, rhs: Self) -> Option<Self> {
+        self.checked_div(rhs)
+    }
+}
+
+/// An integer modulo [`Modular::PRIME`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Modular(i128);
+
+impl Modular {
+    /// 2⁶¹ - 1, small enough that the product of two residues fits an `i128`.
+    const PRIME: i128 = (1 << 61) - 1;
+
+    fn new(value: i128) -> Self {
+        Self(value.rem_euclid(Self::PRIME))
+    }
+
+    /// The integer closest to zero with this residue.
+    fn lift(self) -> i128 {
+        if self.0 > Self::PRIME / 2 {
+            self.0 - Self::PRIME
+        } else {
+            self.0
+        }
+    }
+}
+
+impl Field for Modular {
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn sub_mul(self, a: Self, b: Self) -> Option<Self> {
+        Some(Self::new(self.0 - a.0 * b.0 % Self::PRIME))
+    }
+
+    fn div(self, rhs: Self) -> Option<Self> {
+        Some(Self::new(self.0 * modular_inverse(rhs.0, Self::PRIME)?))
+    }
+}
+
+/// The number of unknowns, and the rows of `coefficients` each followed by
+/// its constant, which panics unless every row has the same length and
+/// there is a constant for each.
+fn augmented<T: Copy, F: Field>(
+    coefficients: &[Vec<T>],
+    constants: &[T],
+    convert: impl Fn(T) -> F,
+) -> (usize, Vec<Vec<F>>) {
+    assert_eq!(coefficients.len(), constants.len(), "one constant per row");
+    let unknowns = coefficients.first().map_or(0, Vec::len);
+    let rows = coefficients
+        .iter()
+        .zip(constants)
+        .map(|(row, constant)| {
+            assert_eq!(unknowns, row.len(), "rows of equal length");
+            row.iter()
+                .chain(std::iter::once(constant))
+                .map(|value| convert(*value))
+                .collect()
+        })
+        .collect();
+    (unknowns, rows)
+}
+
+/// Gauss-Jordan elimination of the augmented `rows`, giving the value of
+/// each of the `unknowns`.
+fn eliminate<F: Field>(mut rows: Vec<Vec<F>>, unknowns: usize) -> Result<Vec<F>, SolveError> {
+    let mut pivots = 0;
+    for column in 0..unknowns {
+        let Some(found) = (pivots..rows.len()).find(|row| !rows[*row][column].is_zero()) else {
+            continue;
+        };
+        rows.swap(pivots, found);
+        let pivot = rows[pivots][column];
+        for value in &mut rows[pivots][column..] {
+            *value = value.div(pivot).ok_or(SolveError::Overflow)?;
+        }
+        let pivot_row = rows[pivots].clone();
+        for (id, row) in rows.iter_mut().enumerate() {
+            let factor = row[column];
+            if id == pivots || factor.is_zero() {
+                continue;
+            }
+            for (value, pivot_value) in row[column..].iter_mut().zip(&pivot_row[column..]) {
+                *value = value
+                    .sub_mul(factor, *pivot_value)
+                    .ok_or(SolveError::Overflow)?;
+            }
+        }
+        pivots += 1;
+    }
+    // Every coefficient left below the pivots is zero, so each constant must be too
+    if rows[pivots..].iter().any(|row| !row[unknowns].is_zero()) {
+        return Err(SolveError::Inconsistent);
+    }
+    if pivots < unknowns {
+        return Err(SolveError::Underdetermined {
+            free: unknowns - pivots,
+        });
+    }
+    Ok(rows
+        .into_iter()
+        .take(unknowns)
+        .map(|row| row[unknowns])
+        .collect())
+}
+
+/// The single solution `x` of the equations `coefficients · x = constants`,
+/// with a row of coefficients for each equation.
+///
+/// There may be more equations than unknowns, as long as they agree.
+pub fn solve(
+    coefficients: &[Vec<Rational>],
+    constants: &[Rational],
+) -> Result<Vec<Rational>, SolveError> {
+    let (unknowns, rows) = augmented(coefficients, constants, |value| value);
+    eliminate(rows, unknowns)
+}
+
+/// The single solution `x` of the equations `coefficients · x = constants`,
+/// which must be whole numbers.
+///
+/// Rather than the fractions [`solve`] would need, which soon outgrow an
+/// `i128`, this works modulo a 61-bit prime, checking the answer against
+/// the original equations. So it can only find solutions up to about 10¹⁸
+/// in size, though the equations themselves can be much larger.
+pub fn solve_integer(
+    coefficients: &[Vec<i128>],
+    constants: &[i128],
+) -> Result<Vec<i128>, SolveError> {
+    let (unknowns, rows) = augmented(coefficients, constants, Modular::new);
+    let solution = eliminate(rows, unknowns)?
+        .into_iter()
+        .map(Modular::lift)
+        .collect::<Vec<_>>();
+    for (row, constant) in coefficients.iter().zip(constants) {
+        let total = row
+            .iter()
+            .zip(&solution)
+            .try_fold(0i128, |total, (a, x)| total.checked_add(a.checked_mul(*x)?))
+            .ok_or(SolveError::Overflow)?;
+        if total != *constant {
+            return Err(SolveError::NotIntegral);
+        }
+    }
+    Ok(solution)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rationals(values: &[i128]) -> Vec<Rational> {
+        values.iter().map(|v| Rational::integer(*v)).collect()
+    }
+
+    #[test]
+    fn rational() {
+        // x + y + z = 6, 2y + 5z = -4, 2x + 5y - z = 27
+        let coefficients = [
+            rationals(&[1, 1, 1]),
+            rationals(&[0, 2, 5]),
+            rationals(&[2, 5, -1]),
+        ];
+        assert_eq!(
+            Ok(rationals(&[5, 3, -2])),
+            solve(&coefficients, &rationals(&[6, -4, 27]))
+        );
+
+        // 2x = 1, with a second agreeing equation
+        assert_eq!(
+            Ok(vec![Rational::new(1, 2)]),
+            solve(&[rationals(&[2]), rationals(&[4])], &rationals(&[1, 2]))
+        );
+
+        let parallel = [rationals(&[1, 1]), rationals(&[2, 2])];
+        assert_eq!(
+            Err(SolveError::Underdetermined { free: 1 }),
+            solve(&parallel, &rationals(&[1, 2]))
+        );
+        assert_eq!(
+            Err(SolveError::Inconsistent),
+            solve(&parallel, &rationals(&[1, 3]))
+        );
+    }
+
+    #[test]
+    fn integer() {
+        // Coefficients and constants far too big to eliminate as fractions
+        let big = 400_000_000_000_000i128;
+        let solution = [big + 3, -big / 2, 123];
+        let coefficients = vec![
+            vec![big * 1000, -7, big + 11],
+            vec![-13, big * 900, 17],
+            vec![big * 3, big * 5, -big * 800],
+            vec![1, 1, 1],
+        ];
+        let constants = coefficients
+            .iter()
+            .map(|row| row.iter().zip(solution).map(|(a, x)| a * x).sum())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Ok(solution.to_vec()),
+            solve_integer(&coefficients, &constants)
+        );
+
+        assert_eq!(
+            Err(SolveError::NotIntegral),
+            solve_integer(&[vec![2]], &[1])
+        );
+        assert_eq!(
+            Err(SolveError::Underdetermined { free: 2 }),
+            solve_integer(&[vec![1, 1, 1]], &[3])
+        );
+        assert_eq!(
+            Err(SolveError::Inconsistent),
+            solve_integer(&[vec![1, 1], vec![1, 1]], &[3, 4])
+        );
+    }
+}
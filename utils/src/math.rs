@@ -1,30 +1,236 @@
+use std::fmt::Debug;
+
+mod polynomial;
+mod rational;
+
+pub use polynomial::{finite_differences, lagrange, lowest_degree_differences, Differences};
+pub use rational::Rational;
+
+/// The primitive integer types, so the helpers here can be written once.
+pub trait Integer: Copy + Ord + Debug {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_neg(self) -> Option<Self>;
+    /// The remainder, which never overflows as `checked_rem` can for
+    /// `MIN % -1`.
+    fn wrapping_rem(self, rhs: Self) -> Self;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_div(self, rhs)
+                }
+                fn checked_neg(self) -> Option<Self> {
+                    <$t>::checked_neg(self)
+                }
+                fn wrapping_rem(self, rhs: Self) -> Self {
+                    <$t>::wrapping_rem(self, rhs)
+                }
+            }
+        )*
+    };
+}
+integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+fn checked_abs<T: Integer>(x: T) -> Option<T> {
+    if x < T::ZERO {
+        x.checked_neg()
+    } else {
+        Some(x)
+    }
+}
+
 // From https://doc.rust-lang.org/std/ops/trait.Div.html
 // Euclid's two-thousand-year-old algorithm for finding the greatest common
 // divisor.
-pub fn greatest_common_divisor(x: u64, y: u64) -> u64 {
+/// The (non-negative) greatest common divisor of `x` and `y`, or `None` if
+/// it is too big for the type, which is only possible for `MIN` of a signed
+/// type.
+pub fn greatest_common_divisor<T: Integer>(x: T, y: T) -> Option<T> {
     let mut x = x;
     let mut y = y;
-    while y != 0 {
+    while y != T::ZERO {
         let t = y;
-        y = x % y;
+        y = x.wrapping_rem(y);
         x = t;
     }
-    x
+    checked_abs(x)
+}
+
+/// The (non-negative) lowest common multiple of `a` and `b`, or `None` if it
+/// overflows.
+pub fn lowest_common_multiple<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    let divisor = greatest_common_divisor(a, b)?;
+    checked_abs(a.checked_div(divisor)?.checked_mul(b)?)
 }
 
-pub fn lowest_common_multiple(a: u64, b: u64) -> u64 {
-    a * b / greatest_common_divisor(a, b)
+pub fn lowest_common_multiple_3<T: Integer>(a: T, b: T, c: T) -> Option<T> {
+    lowest_common_multiple(a, lowest_common_multiple(b, c)?)
 }
 
-pub fn lowest_common_multiple_3(a: u64, b: u64, c: u64) -> u64 {
-    lowest_common_multiple(a, lowest_common_multiple(b, c))
+/// The lowest common multiple of every number in `nums`, which is one for
+/// none at all, or `None` if it overflows.
+pub fn lowest_common_multiple_many<T: Integer>(nums: &[T]) -> Option<T> {
+    nums.iter()
+        .try_fold(T::ONE, |acc, n| lowest_common_multiple(acc, *n))
 }
 
-pub fn lowest_common_multiple_many(nums: &[u64]) -> u64 {
-    if nums.len() == 1 {
-        nums[0]
+/// Extended Euclid: `(g, x, y)` such that `a * x + b * y == g`, where `g`
+/// is the non-negative greatest common divisor of `a` and `b`.
+pub fn extended_euclid(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
     } else {
-        let others = lowest_common_multiple_many(&nums[1..]);
-        nums[0] * others / greatest_common_divisor(nums[0], others)
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`, if `a` and
+/// `modulus` are coprime.
+pub fn modular_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (g, x, _) = extended_euclid(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// `base` to the power `exponent`, modulo `modulus`.
+pub fn modular_pow(base: u64, exponent: u64, modulus: u64) -> u64 {
+    // Intermediate products of two values below the modulus fit in a u128
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut exponent = exponent;
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result as u64
+}
+
+/// Solve the simultaneous congruences `x ≡ remainder (mod modulus)` for each
+/// `(remainder, modulus)`, which need not be coprime.
+///
+/// Returns `(x, period)` with `x` the smallest non-negative solution, every
+/// other being `x` plus a multiple of `period`, or `None` if the congruences
+/// contradict each other or the period overflows.
+pub fn chinese_remainder(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    congruences
+        .iter()
+        .try_fold((0, 1), |(x, period), (remainder, modulus)| {
+            let (g, p, _) = extended_euclid(period, *modulus);
+            let difference = remainder - x;
+            if difference % g != 0 {
+                return None;
+            }
+            // x + period * k ≡ remainder, with k = (difference / g) * p
+            let step = modulus / g;
+            let k = (difference / g)
+                .rem_euclid(step)
+                .checked_mul(p.rem_euclid(step))?
+                % step;
+            let combined = period.checked_mul(step)?;
+            let x = x.checked_add(period.checked_mul(k)?)?.rem_euclid(combined);
+            Some((x, combined))
+        })
+}
+
+/// The first step at which every one of `cycles` hits at once, where each
+/// `(offset, period)` first hits after `offset` steps and then every
+/// `period` steps after that; `None` if they never line up.
+pub fn first_common_step(cycles: &[(i128, i128)]) -> Option<i128> {
+    let congruences = cycles
+        .iter()
+        .map(|(offset, period)| (offset.rem_euclid(*period), *period))
+        .collect::<Vec<_>>();
+    let (x, period) = chinese_remainder(&congruences)?;
+    // Only count hits once every cycle has started
+    let earliest = cycles.iter().map(|(offset, _)| *offset).max().unwrap_or(0);
+    if x >= earliest {
+        return Some(x);
+    }
+    let periods = (earliest - x + period - 1) / period;
+    x.checked_add(periods.checked_mul(period)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(Some(6), greatest_common_divisor(12u8, 18));
+        assert_eq!(Some(6), greatest_common_divisor(-12i32, 18));
+        assert_eq!(Some(5), greatest_common_divisor(0i64, -5));
+        assert_eq!(None, greatest_common_divisor(i8::MIN, 0));
+
+        assert_eq!(Some(36), lowest_common_multiple(12usize, 18));
+        assert_eq!(Some(36), lowest_common_multiple(-12i16, 18));
+        assert_eq!(None, lowest_common_multiple(200u8, 3));
+        assert_eq!(Some(0), lowest_common_multiple(0u32, 7));
+        assert_eq!(Some(60), lowest_common_multiple_3(3u64, 4, 5));
+
+        assert_eq!(Some(1), lowest_common_multiple_many::<u64>(&[]));
+        assert_eq!(
+            Some(2520),
+            lowest_common_multiple_many(&(1u64..=10).collect::<Vec<_>>())
+        );
+        assert_eq!(None, lowest_common_multiple_many(&[u64::MAX, 2]));
+    }
+
+    #[test]
+    fn modular() {
+        let (g, x, y) = extended_euclid(240, 46);
+        assert_eq!(2, g);
+        assert_eq!(2, 240 * x + 46 * y);
+
+        assert_eq!(Some(4), modular_inverse(3, 11));
+        assert_eq!(Some(7), modular_inverse(-3, 11));
+        assert_eq!(None, modular_inverse(4, 8));
+
+        assert_eq!(445, modular_pow(4, 13, 497));
+        assert_eq!(0, modular_pow(5, 0, 1));
+        assert_eq!(1, modular_pow(u64::MAX - 1, u64::MAX - 1, u64::MAX));
+    }
+
+    #[test]
+    fn crt() {
+        assert_eq!(
+            Some((23, 105)),
+            chinese_remainder(&[(2, 3), (3, 5), (2, 7)])
+        );
+        assert_eq!(Some((10, 12)), chinese_remainder(&[(4, 6), (2, 4)]));
+        assert_eq!(None, chinese_remainder(&[(1, 6), (2, 4)]));
+        assert_eq!(Some((0, 1)), chinese_remainder(&[]));
+        assert_eq!(Some((3, 4)), chinese_remainder(&[(-1, 4)]));
+
+        assert_eq!(Some(12), first_common_step(&[(3, 3), (4, 4)]));
+        assert_eq!(Some(17), first_common_step(&[(2, 3), (7, 5)]));
+        assert_eq!(None, first_common_step(&[(1, 2), (2, 4)]));
     }
 }
@@ -0,0 +1,141 @@
+use super::Rational;
+
+/// The table of finite differences of samples taken at `x = 0, 1, 2, ...`,
+/// down to the first row which is constant, describing the polynomial
+/// through them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Differences {
+    rows: Vec<Vec<i128>>,
+}
+
+/// Build the difference table for `samples`, or `None` if the differences
+/// never settle to a constant row of at least two values, so there are too
+/// few samples to pin down the polynomial.
+pub fn finite_differences(samples: &[i128]) -> Option<Differences> {
+    difference_table(samples, 2)
+}
+
+/// Build the difference table for `samples` like [`finite_differences`],
+/// but taking a single value left in the last row as constant. So any
+/// samples give a polynomial through them, of degree at most one less than
+/// their number, and this is only `None` if there are none or it overflows.
+pub fn lowest_degree_differences(samples: &[i128]) -> Option<Differences> {
+    difference_table(samples, 1)
+}
+
+/// The difference table down to the first constant row of at least
+/// `shortest` values.
+fn difference_table(samples: &[i128], shortest: usize) -> Option<Differences> {
+    let mut rows = vec![samples.to_vec()];
+    loop {
+        let last = rows.last().unwrap();
+        if last.len() < shortest || last.is_empty() {
+            return None;
+        }
+        if last.iter().all(|v| *v == last[0]) {
+            return Some(Differences { rows });
+        }
+        let next = last
+            .windows(2)
+            .map(|pair| pair[1].checked_sub(pair[0]))
+            .collect::<Option<Vec<_>>>()?;
+        rows.push(next);
+    }
+}
+
+impl Differences {
+    /// The degree of the polynomial through the samples.
+    pub fn degree(&self) -> usize {
+        self.rows.len() - 1
+    }
+
+    /// The rows of the table, starting with the samples themselves.
+    pub fn rows(&self) -> &[Vec<i128>] {
+        &self.rows
+    }
+
+    /// The polynomial's value at `x`, which may lie before, among or after
+    /// the samples, or `None` if it overflows.
+    pub fn at(&self, x: i128) -> Option<i128> {
+        // Newton's forward differences: the sum of C(x, k) * Δᵏf(0)
+        let mut total = 0i128;
+        let mut binomial = 1i128;
+        for (k, row) in self.rows.iter().enumerate() {
+            if k > 0 {
+                // C(x, k) = C(x, k - 1) * (x - k + 1) / k, which divides exactly
+                let k = k as i128;
+                binomial = binomial.checked_mul(x.checked_sub(k - 1)?)? / k;
+            }
+            total = total.checked_add(binomial.checked_mul(row[0])?)?;
+        }
+        Some(total)
+    }
+
+    /// The value following the last sample.
+    pub fn next(&self) -> Option<i128> {
+        self.at(self.rows[0].len() as i128)
+    }
+
+    /// The value preceding the first sample.
+    pub fn previous(&self) -> Option<i128> {
+        self.at(-1)
+    }
+}
+
+/// The value at `x` of the lowest degree polynomial through `points`, whose
+/// `x` values must be distinct, or `None` if they are not or it overflows.
+pub fn lagrange(points: &[(i128, i128)], x: i128) -> Option<Rational> {
+    points
+        .iter()
+        .enumerate()
+        .try_fold(Rational::ZERO, |total, (i, (xi, yi))| {
+            let term = points
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .try_fold(Rational::integer(*yi), |term, (_, (xj, _))| {
+                    term.checked_mul(Rational::checked_new(
+                        x.checked_sub(*xj)?,
+                        xi.checked_sub(*xj)?,
+                    )?)
+                })?;
+            total.checked_add(term)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn differences() {
+        let d = finite_differences(&[1, 3, 6, 10, 15, 21]).unwrap();
+        assert_eq!(2, d.degree());
+        assert_eq!(Some(28), d.next());
+        assert_eq!(Some(0), d.previous());
+        assert_eq!(Some(5050), d.at(99));
+        assert_eq!(Some(1), d.at(-3));
+
+        assert_eq!(Some(7), finite_differences(&[7, 7]).unwrap().next());
+        // Too few samples to see the differences become constant
+        assert_eq!(None, finite_differences(&[1, 2, 4]));
+        assert_eq!(None, finite_differences(&[]));
+
+        // Unless a single value is taken to be constant
+        let d = lowest_degree_differences(&[1, 2, 4]).unwrap();
+        assert_eq!(2, d.degree());
+        assert_eq!(Some(7), d.next());
+        assert_eq!(Some(5), lowest_degree_differences(&[1, 3]).unwrap().next());
+        assert_eq!(Some(5), lowest_degree_differences(&[5]).unwrap().previous());
+        assert_eq!(None, lowest_degree_differences(&[]));
+    }
+
+    #[test]
+    fn interpolation() {
+        let points = [(1, 1), (3, 9), (4, 16)];
+        assert_eq!(Some(Rational::integer(4)), lagrange(&points, 2));
+        assert_eq!(Some(Rational::integer(100)), lagrange(&points, -10));
+        assert_eq!(Some(Rational::new(1, 2)), lagrange(&[(0, 0), (2, 1)], 1));
+        assert_eq!(None, lagrange(&[(0, 0), (0, 1)], 1));
+    }
+}
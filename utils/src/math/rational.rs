@@ -0,0 +1,216 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::greatest_common_divisor;
+
+/// An exact fraction, always kept in lowest terms with a positive
+/// denominator.
+///
+/// The operators panic if a result no longer fits in an `i128`; the
+/// `checked_` methods return `None` instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Self = Self::integer(0);
+    pub const ONE: Self = Self::integer(1);
+
+    /// `numerator / denominator`, which panics if `denominator` is zero.
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        Self::checked_new(numerator, denominator).expect("invalid rational")
+    }
+
+    /// `numerator / denominator`, or `None` if `denominator` is zero.
+    pub fn checked_new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = greatest_common_divisor(numerator, denominator)?;
+        let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+        Some(if denominator < 0 {
+            Self {
+                numerator: numerator.checked_neg()?,
+                denominator: denominator.checked_neg()?,
+            }
+        } else {
+            Self {
+                numerator,
+                denominator,
+            }
+        })
+    }
+
+    pub const fn integer(value: i128) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The value as an integer, if it is one.
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn signum(&self) -> i128 {
+        self.numerator.signum()
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let divisor = greatest_common_divisor(self.denominator, rhs.denominator)?;
+        let numerator = self
+            .numerator
+            .checked_mul(rhs.denominator / divisor)?
+            .checked_add(rhs.numerator.checked_mul(self.denominator / divisor)?)?;
+        Self::checked_new(
+            numerator,
+            self.denominator.checked_mul(rhs.denominator / divisor)?,
+        )
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        // Cancel across first to keep the intermediate products small
+        let a = greatest_common_divisor(self.numerator, rhs.denominator)?.max(1);
+        let b = greatest_common_divisor(rhs.numerator, self.denominator)?.max(1);
+        Self::checked_new(
+            (self.numerator / a).checked_mul(rhs.numerator / b)?,
+            (self.denominator / b).checked_mul(rhs.denominator / a)?,
+        )
+    }
+
+    /// The quotient, or `None` if it overflows or `rhs` is zero.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_mul(Self::checked_new(rhs.denominator, rhs.numerator)?)
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            numerator: self.numerator.checked_neg()?,
+            denominator: self.denominator,
+        })
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Self::integer(value)
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (
+            self.numerator.checked_mul(other.denominator),
+            other.numerator.checked_mul(self.denominator),
+        ) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            // Too big to cross-multiply, so compare the difference instead
+            _ => self
+                .checked_sub(*other)
+                .expect("rational overflow")
+                .signum()
+                .cmp(&0),
+        }
+    }
+}
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+macro_rules! rational_operator {
+    ($trait:ident, $method:ident, $checked:ident) => {
+        impl $trait for Rational {
+            type Output = Rational;
+
+            fn $method(self, rhs: Self) -> Self::Output {
+                self.$checked(rhs).expect("rational overflow")
+            }
+        }
+    };
+}
+rational_operator!(Add, add, checked_add);
+rational_operator!(Sub, sub, checked_sub);
+rational_operator!(Mul, mul, checked_mul);
+rational_operator!(Div, div, checked_div);
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().expect("rational overflow")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let half = Rational::new(2, 4);
+        assert_eq!((1, 2), (half.numerator(), half.denominator()));
+        assert_eq!(Rational::new(-1, 3), Rational::new(2, -6));
+        assert_eq!(Rational::new(5, 6), half + Rational::new(1, 3));
+        assert_eq!(Rational::new(1, 6), half - Rational::new(1, 3));
+        assert_eq!(Rational::ONE, half * Rational::integer(2));
+        assert_eq!(Rational::new(3, 2), half / Rational::new(1, 3));
+        assert_eq!(None, half.checked_div(Rational::ZERO));
+        assert_eq!(
+            None,
+            Rational::integer(i128::MAX).checked_add(Rational::ONE)
+        );
+        assert_eq!(Some(3), (half * Rational::integer(6)).to_integer());
+        assert_eq!(None, half.to_integer());
+        assert_eq!("-1/2", (-half).to_string());
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(Rational::new(1, 3) < Rational::new(1, 2));
+        assert!(Rational::new(-1, 2) < Rational::ZERO);
+        let big = Rational::new(i128::MAX, 3);
+        assert!(big > Rational::new(i128::MAX - 1, 3));
+    }
+}
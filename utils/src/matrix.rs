@@ -2,8 +2,9 @@ use std::cmp::{max, min};
 use std::collections::HashMap;
 use std::fmt::Display;
 
+/// A sparse grid of cells keyed by `(x, y)`.
 #[derive(Debug, Default, Clone)]
-//TODO #[deprecated(since="0.5.0", note="please use `Grid` instead")]
+#[deprecated(note = "use `DenseMatrix` for dense grids, or `Grid` for sparse cells")]
 pub struct Matrix<T> {
     data: HashMap<(isize, isize), T>,
     max_x: isize,
@@ -12,6 +13,7 @@ pub struct Matrix<T> {
     min_y: isize,
 }
 
+#[allow(deprecated)]
 impl<T> Matrix<T>
 where
     T: Default + Display + Clone,
@@ -89,12 +91,13 @@ where
         }
     }
 
-    pub fn sparse_iter(&self) -> std::collections::hash_map::Iter<(isize, isize), T> {
+    pub fn sparse_iter(&self) -> std::collections::hash_map::Iter<'_, (isize, isize), T> {
         self.data.iter()
     }
 }
 
 #[cfg(test)]
+#[allow(deprecated)]
 mod tests {
     use super::*;
 
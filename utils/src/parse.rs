@@ -0,0 +1,359 @@
+//! Reading puzzle input, reporting where it is malformed.
+//!
+//! Input is read as numbered [`Line`]s, or as [`blocks`] of lines separated
+//! by blank lines. A [`Line`] splits and parses slices of its own text, so
+//! that errors point at the offending characters:
+//!
+//! ```
+//! use utils::parse::Line;
+//!
+//! let line = Line::new(1, "Card 1: 41 48 83 | 83 86  6");
+//! let (_, numbers) = line.key_value().unwrap();
+//! let (winning, have) = line.split_once(numbers, "|").unwrap();
+//! assert_eq!(vec![41, 48, 83], line.numbers::<u32>(winning).unwrap());
+//! assert_eq!(vec![83, 86, 6], line.numbers::<u32>(have).unwrap());
+//! ```
+use std::{
+    fmt::Display,
+    io::{BufRead, BufReader},
+    str::FromStr,
+};
+
+use crate::DenseMatrix;
+
+/// An error found while reading or parsing puzzle input.
+#[derive(Debug)]
+pub enum ParseError {
+    Io(std::io::Error),
+    /// Unexpected input at `line` and `column` (both counting from 1), which
+    /// spans `width` characters of `text`.
+    Syntax {
+        line: usize,
+        column: usize,
+        width: usize,
+        expected: String,
+        text: String,
+    },
+}
+impl Display for ParseError {
+    /// Syntax errors are shown with the offending line, and a caret under the
+    /// unexpected input.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Syntax {
+                line,
+                column,
+                width,
+                expected,
+                text,
+            } => {
+                let gutter = " ".repeat(line.to_string().len());
+                writeln!(f, "line {line}, column {column}: expected {expected}")?;
+                writeln!(f, "{line} | {text}")?;
+                write!(
+                    f,
+                    "{gutter} | {}{}",
+                    " ".repeat(column - 1),
+                    "^".repeat((*width).max(1))
+                )
+            }
+        }
+    }
+}
+impl std::error::Error for ParseError {}
+impl From<std::io::Error> for ParseError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// A numbered line of input, against which errors are reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub number: usize,
+    pub text: String,
+}
+impl Line {
+    pub fn new(number: usize, text: &str) -> Self {
+        Self {
+            number,
+            text: text.to_owned(),
+        }
+    }
+
+    /// An error pointing at `at`, which should be a slice of this line's
+    /// text. Any other string points at the end of the line.
+    pub fn error(&self, at: &str, expected: impl Display) -> ParseError {
+        let start = self.text.as_ptr() as usize;
+        let offset = (at.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|offset| offset + at.len() <= self.text.len())
+            .unwrap_or(self.text.len());
+        ParseError::Syntax {
+            line: self.number,
+            column: self.text[..offset].chars().count() + 1,
+            width: at.chars().count(),
+            expected: expected.to_string(),
+            text: self.text.clone(),
+        }
+    }
+
+    /// Parse `at`, ignoring surrounding whitespace.
+    pub fn parse<T: FromStr>(&self, at: &str, expected: impl Display) -> Result<T, ParseError> {
+        at.trim()
+            .parse()
+            .map_err(|_| self.error(at.trim(), expected))
+    }
+
+    /// Split `at` on the first occurrence of `delimiter`.
+    pub fn split_once<'a>(
+        &self,
+        at: &'a str,
+        delimiter: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        at.split_once(delimiter)
+            .ok_or_else(|| self.error(&at[at.len()..], format!("'{delimiter}'")))
+    }
+
+    /// Split this line as a `key: value` header, trimming both.
+    pub fn key_value(&self) -> Result<(&str, &str), ParseError> {
+        let (key, value) = self.split_once(&self.text, ":")?;
+        Ok((key.trim(), value.trim()))
+    }
+
+    /// The value of a `key: value` header, which must have the given `key`.
+    pub fn value_of(&self, key: &str) -> Result<&str, ParseError> {
+        match self.key_value()? {
+            (found, value) if found == key => Ok(value),
+            (found, _) => Err(self.error(found, format!("'{key}'"))),
+        }
+    }
+
+    /// Parse the whitespace separated numbers (or other values) in `at`.
+    pub fn numbers<T: FromStr>(&self, at: &str) -> Result<Vec<T>, ParseError> {
+        at.split_whitespace()
+            .map(|value| self.parse(value, "a number"))
+            .collect()
+    }
+
+    /// Parse exactly `N` whitespace separated numbers in `at`.
+    pub fn numbers_array<T: FromStr, const N: usize>(
+        &self,
+        at: &str,
+    ) -> Result<[T; N], ParseError> {
+        let values = self.numbers(at)?;
+        let count = values.len();
+        values
+            .try_into()
+            .map_err(|_| self.error(at.trim(), format!("{N} numbers, found {count}")))
+    }
+
+    /// Parse the values in `at` separated by `separator`, ignoring whitespace
+    /// around each value.
+    pub fn separated<T: FromStr>(
+        &self,
+        at: &str,
+        separator: &str,
+        expected: impl Display,
+    ) -> Result<Vec<T>, ParseError> {
+        at.split(separator)
+            .map(|value| self.parse(value, &expected))
+            .collect()
+    }
+
+    /// Parse exactly `N` values in `at` separated by `separator`.
+    pub fn separated_array<T: FromStr, const N: usize>(
+        &self,
+        at: &str,
+        separator: &str,
+        expected: impl Display,
+    ) -> Result<[T; N], ParseError> {
+        let values = self.separated(at, separator, expected)?;
+        let count = values.len();
+        values
+            .try_into()
+            .map_err(|_| self.error(at.trim(), format!("{N} values, found {count}")))
+    }
+}
+
+/// The lines of `reader`, numbered from 1.
+pub fn lines<T: std::io::Read>(
+    reader: BufReader<T>,
+) -> impl Iterator<Item = Result<Line, ParseError>> {
+    reader.lines().enumerate().map(|(id, line)| {
+        Ok(Line {
+            number: id + 1,
+            text: line?,
+        })
+    })
+}
+
+/// The blocks of non-blank lines of `reader`, separated by blank lines.
+pub fn blocks<T: std::io::Read>(
+    reader: BufReader<T>,
+) -> impl Iterator<Item = Result<Vec<Line>, ParseError>> {
+    let mut lines = lines(reader);
+    std::iter::from_fn(move || {
+        let mut block = Vec::new();
+        for line in lines.by_ref() {
+            match line {
+                Err(e) => return Some(Err(e)),
+                Ok(line) if line.text.trim().is_empty() => {
+                    if !block.is_empty() {
+                        break;
+                    }
+                }
+                Ok(line) => block.push(line),
+            }
+        }
+        (!block.is_empty()).then_some(Ok(block))
+    })
+}
+
+/// Read `lines` as a grid, with `(0, 0)` at the top left, mapping each
+/// character to a cell. A character which maps to `None` is an error, and
+/// cells past the end of a short line hold `T::default()`.
+pub fn grid<T>(
+    lines: &[Line],
+    cell: impl Fn(char) -> Option<T>,
+    expected: impl Display,
+) -> Result<DenseMatrix<T>, ParseError>
+where
+    T: Default + Display + Clone,
+{
+    let mut grid = sized_grid(lines);
+    for (y, line) in lines.iter().enumerate() {
+        for (x, (i, c)) in line.text.char_indices().enumerate() {
+            let value =
+                cell(c).ok_or_else(|| line.error(&line.text[i..i + c.len_utf8()], &expected))?;
+            grid.set(x as isize, y as isize, value);
+        }
+    }
+    Ok(grid)
+}
+
+/// Read `lines` as a grid of characters, with `(0, 0)` at the top left.
+pub fn char_grid(lines: &[Line]) -> DenseMatrix<char> {
+    let mut grid = sized_grid(lines);
+    for (y, line) in lines.iter().enumerate() {
+        for (x, c) in line.text.chars().enumerate() {
+            grid.set(x as isize, y as isize, c);
+        }
+    }
+    grid
+}
+
+/// A grid of default cells as wide as the longest of `lines`.
+fn sized_grid<T: Default + Display + Clone>(lines: &[Line]) -> DenseMatrix<T> {
+    let width = lines
+        .iter()
+        .map(|line| line.text.chars().count())
+        .max()
+        .unwrap_or(0);
+    DenseMatrix::with_size(width, lines.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn diagnostic() {
+        let line = Line::new(12, "Game 3: 8 grean, 6 blue");
+        let e = line.error(&line.text[10..15], "a color");
+        assert_eq!(
+            "line 12, column 11: expected a color\n12 | Game 3: 8 grean, 6 blue\n   |           ^^^^^",
+            e.to_string()
+        );
+    }
+
+    #[test]
+    fn helpers() {
+        let line = Line::new(1, "a: 12 x");
+        let (_, value) = line.split_once(&line.text, ":").unwrap();
+        assert_eq!(
+            Ok(12),
+            line.parse::<u32>(&value[..3], "a number").map_err(|_| ())
+        );
+        let Err(ParseError::Syntax { column, width, .. }) = line.parse::<u32>(value, "a number")
+        else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!((4, 4), (column, width));
+        let Err(ParseError::Syntax { column, .. }) = line.split_once(value, "|") else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!(8, column);
+
+        let line = Line::new(2, "1,2, 3 @ 4,5");
+        let (position, velocity) = line.split_once(&line.text, "@").unwrap();
+        assert_eq!(
+            [1, 2, 3],
+            line.separated_array::<i32, 3>(position, ",", "a number")
+                .unwrap()
+        );
+        let Err(ParseError::Syntax { expected, .. }) =
+            line.separated_array::<i32, 3>(velocity, ",", "a number")
+        else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!("3 values, found 2", expected);
+
+        let lines = lines(BufReader::new("a\nb".as_bytes()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(vec![Line::new(1, "a"), Line::new(2, "b")], lines);
+    }
+
+    #[test]
+    fn headers_and_lists() {
+        let line = Line::new(1, "seeds: 79 14  55 13");
+        assert_eq!(
+            Ok(("seeds", "79 14  55 13")),
+            line.key_value().map_err(|_| ())
+        );
+        let seeds = line.value_of("seeds").unwrap();
+        assert_eq!(vec![79, 14, 55, 13], line.numbers::<u64>(seeds).unwrap());
+        assert!(line.value_of("Time").is_err());
+        assert!(line.numbers_array::<u64, 3>(seeds).is_err());
+
+        let line = Line::new(2, "50 98 2");
+        assert_eq!(
+            [50, 98, 2],
+            line.numbers_array::<i64, 3>(&line.text).unwrap()
+        );
+
+        let line = Line::new(3, "1, 1,x");
+        let Err(ParseError::Syntax { column, .. }) =
+            line.separated::<u8>(&line.text, ",", "a group size")
+        else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!(6, column);
+    }
+
+    #[test]
+    fn blocks_and_grids() {
+        let input = "#.\n.#\n\n\n12\n3x\n";
+        let blocks = blocks(BufReader::new(input.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(2, blocks.len());
+        assert_eq!(5, blocks[1][0].number);
+
+        let grid = char_grid(&blocks[0]);
+        assert_eq!(Some(&'#'), grid.get(1, 1));
+        assert_eq!((1, 1), grid.dimensions());
+
+        let digits = |c: char| c.to_digit(10);
+        let grid = super::grid(&blocks[1][..1], digits, "a digit").unwrap();
+        assert_eq!(Some(&2), grid.get(1, 0));
+        let Err(ParseError::Syntax { line, column, .. }) =
+            super::grid(&blocks[1], digits, "a digit")
+        else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!((6, 2), (line, column));
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    iter::Step,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// A point, or a vector, in three dimensions.
+#[derive(Debug, Clone, Copy, Default, PartialOrd, Ord, Eq, Hash, PartialEq)]
+pub struct Point3<T> {
+    x: T,
+    y: T,
+    z: T,
+}
+
+impl<T: Copy> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn x(&self) -> T {
+        self.x
+    }
+
+    pub fn y(&self) -> T {
+        self.y
+    }
+
+    pub fn z(&self) -> T {
+        self.z
+    }
+
+    /// Combine with `other` one axis at a time.
+    pub(crate) fn zip_with(&self, other: &Self, f: impl Fn(T, T) -> T) -> Self {
+        Self {
+            x: f(self.x, other.x),
+            y: f(self.y, other.y),
+            z: f(self.z, other.z),
+        }
+    }
+}
+
+impl<T> Point3<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        Self {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+}
+
+impl<T: Copy + Step> Point3<T> {
+    /// The 6 points sharing a face with this one.
+    ///
+    /// Like the rest of the neighbourhoods, this leaves out any points which
+    /// `T` cannot hold, such as below zero for an unsigned type.
+    pub fn cardinal(&self) -> Vec<Self> {
+        self.neighbourhood(|moved| moved == 1)
+    }
+
+    /// The 26 points sharing a face, edge or corner with this one.
+    pub fn neighbours(&self) -> Vec<Self> {
+        self.neighbourhood(|_| true)
+    }
+
+    /// The neighbours for which `include` holds of the number of axes moved.
+    fn neighbourhood(&self, include: impl Fn(usize) -> bool) -> Vec<Self> {
+        let shift = |value: T, by: i8| match by {
+            -1 => T::backward_checked(value, 1),
+            1 => T::forward_checked(value, 1),
+            _ => Some(value),
+        };
+        let mut neighbours = Vec::new();
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let moved = [dx, dy, dz].iter().filter(|d| **d != 0).count();
+                    if moved == 0 || !include(moved) {
+                        continue;
+                    }
+                    if let (Some(x), Some(y), Some(z)) =
+                        (shift(self.x, dx), shift(self.y, dy), shift(self.z, dz))
+                    {
+                        neighbours.push(Self { x, y, z });
+                    }
+                }
+            }
+        }
+        neighbours
+    }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> From<Point3<T>> for [T; 3] {
+    fn from(point: Point3<T>) -> Self {
+        [point.x, point.y, point.z]
+    }
+}
+
+impl<T: Copy + Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, |a, b| a + b)
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, |a, b| a - b)
+    }
+}
+
+impl<T: Copy + AddAssign> AddAssign for Point3<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T: Copy + SubAssign> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+/// Scaling by a number.
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+        }
+    }
+}
+
+impl<T: Copy + Neg<Output = T>> Neg for Point3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Point3::new(1, 2, 3);
+        let b = Point3::new(-1, 0, 2);
+        assert_eq!(Point3::new(0, 2, 5), a + b);
+        assert_eq!(Point3::new(2, 2, 1), a - b);
+        assert_eq!(Point3::new(2, 4, 6), a * 2);
+        assert_eq!(Point3::new(-1, -2, -3), -a);
+        assert_eq!(5, a.dot(&b));
+        assert_eq!(Point3::new(4, -5, 2), a.cross(&b));
+        assert_eq!(0, a.dot(&a.cross(&b)));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(b, c);
+        assert_eq!([1, 2, 3], <[i32; 3]>::from(a));
+    }
+
+    #[test]
+    fn neighbourhoods() {
+        let mut cardinal = Point3::new(1, 1, 1).cardinal();
+        cardinal.sort();
+        assert_eq!(
+            vec![
+                Point3::new(0, 1, 1),
+                Point3::new(1, 0, 1),
+                Point3::new(1, 1, 0),
+                Point3::new(1, 1, 2),
+                Point3::new(1, 2, 1),
+                Point3::new(2, 1, 1),
+            ],
+            cardinal
+        );
+        assert_eq!(26, Point3::new(1, 1, 1).neighbours().len());
+
+        // Nothing below zero for unsigned coordinates
+        assert_eq!(3, Point3::new(0u8, 0, 0).cardinal().len());
+        assert_eq!(7, Point3::new(0u8, 0, 0).neighbours().len());
+    }
+}
@@ -1,21 +1,16 @@
-use std::{env, fmt::Display, str::FromStr};
+use std::{env, fmt::Display, str::FromStr, time::Instant};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use tracing::{error, info, instrument, span, Level};
 use tracing_subscriber::fmt::format::FmtSpan;
 use yansi::Paint;
 
-use crate::{load, Solution};
-
-pub trait BaseName {
-    fn base_name(&self) -> Self;
-}
-
-impl BaseName for &str {
-    fn base_name(&self) -> Self {
-        self.rfind('.').map_or(self, |n| &self[..n])
-    }
-}
+use crate::{
+    load,
+    parse::ParseError,
+    sidecar::{self, Entry},
+    Answer, RunContext, Solution, Timing, Timings,
+};
 
 pub fn log_init() {
     // install global collector configured based on RUST_LOG env var.
@@ -31,85 +26,357 @@ pub fn log_init() {
         .init();
 }
 
+/// Answers produced by a single run of a solution against one input file,
+/// each part failing or not independently of the other.
+#[derive(Debug)]
+pub struct Answers {
+    pub part1: Result<Answer>,
+    pub part2: Result<Answer>,
+}
+
+/// Answers expected for an input file, read from `<input>.answers`.
+///
+/// The file holds one `part1: <answer>` and/or `part2: <answer>` line; blank
+/// lines and lines starting with `#` are ignored. A multi-line answer is
+/// written as `part2: |` followed by its lines, indented.
+#[derive(Debug, Clone, Default)]
+pub struct Expected {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail,
+    Unknown,
+}
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pass => write!(f, "PASS"),
+            Self::Fail => write!(f, "FAIL"),
+            Self::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+impl Verdict {
+    fn check(answer: Option<&Answer>, expected: Option<&str>) -> Self {
+        match (answer, expected) {
+            (_, None) => Self::Unknown,
+            (Some(answer), Some(expected)) if answer.matches(expected) => Self::Pass,
+            _ => Self::Fail,
+        }
+    }
+}
+
+/// Outcome of running one day against one of its input files.
+#[derive(Debug)]
+pub struct Report {
+    pub name: String,
+    pub suffix: String,
+    pub result: Result<Answers>,
+    pub expected: Expected,
+    pub timings: Option<Timings>,
+}
+impl Report {
+    /// Verdicts for part1 and part2. A part which failed to produce an
+    /// answer fails if an answer was expected for it.
+    pub fn verdicts(&self) -> [Verdict; 2] {
+        let answers = self.result.as_ref().ok();
+        [
+            Verdict::check(
+                answers.and_then(|a| a.part1.as_ref().ok()),
+                self.expected.part1.as_deref(),
+            ),
+            Verdict::check(
+                answers.and_then(|a| a.part2.as_ref().ok()),
+                self.expected.part2.as_deref(),
+            ),
+        ]
+    }
+
+    /// Whether the input, its expected answers or parameters, or either part
+    /// failed, or an answer did not match.
+    pub fn is_failure(&self) -> bool {
+        let errored = match &self.result {
+            Ok(answers) => answers.part1.is_err() || answers.part2.is_err(),
+            Err(_) => true,
+        };
+        errored || self.verdicts().contains(&Verdict::Fail)
+    }
+}
+
+/// Run the solution `S` for the day `name` against each of the sample and full
+/// inputs, which are read from `input/<name>.<suffix>`. Puzzle parameters for
+/// each input are read from `input/<name>.<suffix>.params`.
+///
+/// Every input is parsed and solved `iterations` times, timing each phase.
 #[instrument]
-pub fn run<S, R>(samples: &[&str], full: &[&str]) -> Result<()>
+pub fn run<S>(name: &str, samples: &[&str], full: &[&str], iterations: usize) -> Vec<Report>
 where
-    S: Solution
-        + TryFrom<std::io::BufReader<std::fs::File>, Error = std::io::Error>
-        + std::fmt::Debug,
-    S::Result: Context<R, anyhow::Error>,
-    R: Display,
+    S: Solution + TryFrom<std::io::BufReader<std::fs::File>, Error = ParseError> + std::fmt::Debug,
 {
-    let basename = std::env::current_exe()
-        .ok()
-        .unwrap()
-        .file_name()
-        .unwrap()
-        .to_str()
-        .map(|s| s.base_name())
-        .unwrap()
-        .to_owned();
-
+    let mut reports = Vec::new();
     span!(Level::INFO, "samples").in_scope(|| {
         for suffix in samples.iter() {
-            let filename = format!("input/{basename}.{suffix}");
-            if let Err(e) = run_solution_file::<S, R>(&filename, false) {
-                error!(
-                    "{}Failed running against '{}': {:?}",
-                    Paint::mask("🎄 "),
-                    filename,
-                    e
-                );
-            }
+            reports.push(run_suffix::<S>(name, suffix, iterations, "🎄 "));
         }
     });
     span!(Level::INFO, "full").in_scope(|| {
         for suffix in full.iter() {
-            let filename = format!("input/{basename}.{suffix}");
-            if let Err(e) = run_solution_file::<S, R>(&filename, true) {
-                error!(
-                    "{}Failed running against '{}': {:?}",
-                    Paint::mask("🎅 "),
-                    filename,
-                    e
-                );
-            }
+            reports.push(run_suffix::<S>(name, suffix, iterations, "🎅 "));
         }
     });
-    Ok(())
+    reports
 }
 
-fn run_solution_file<S, R>(filename: &str, is_full: bool) -> Result<()>
+fn run_suffix<S>(name: &str, suffix: &str, iterations: usize, mask: &str) -> Report
 where
-    S: Solution
-        + TryFrom<std::io::BufReader<std::fs::File>, Error = std::io::Error>
-        + std::fmt::Debug,
-    S::Result: Context<R, anyhow::Error>,
-    R: Display,
+    S: Solution + TryFrom<std::io::BufReader<std::fs::File>, Error = ParseError> + std::fmt::Debug,
 {
-    let mut solution = load::<S>(filename)?;
+    let filename = format!("input/{name}.{suffix}");
+    let (result, expected) = match load_expected(&filename)
+        .and_then(|expected| RunContext::load(&filename).map(|context| (expected, context)))
+    {
+        Ok((expected, context)) => (
+            run_solution_file::<S>(&filename, &context, iterations),
+            expected,
+        ),
+        Err(e) => (Err(e), Expected::default()),
+    };
+    let (result, timings) = match result {
+        Ok((answers, timings)) => (Ok(answers), Some(timings)),
+        Err(e) => (Err(e), None),
+    };
+    if let Err(e) = &result {
+        error!(
+            "{}Failed running against '{}': {:#}",
+            Paint::mask(mask),
+            filename,
+            e
+        );
+    }
+    let report = Report {
+        name: name.to_owned(),
+        suffix: suffix.to_owned(),
+        result,
+        expected,
+        timings,
+    };
+    let [part1, part2] = report.verdicts();
     info!(
-        "{}{} {}: {:?}",
-        Paint::mask("🎄 "),
-        Paint::bold(&Paint::green(filename)),
-        Paint::bold(&Paint::yellow("solution")),
-        solution
-    );
-    solution.analyse(is_full);
-    info!(
-        "{}part1 answer is {}",
-        Paint::mask("🎅 "),
-        Paint::bold(&Paint::red(
-            &solution.answer_part1(is_full).context("part1 failed")?
-        ))
+        "{}{} verdicts: part1 {}, part2 {}",
+        Paint::mask(mask),
+        Paint::bold(&Paint::green(&filename)),
+        part1,
+        part2
     );
+    report
+}
+
+fn load_expected(filename: &str) -> Result<Expected> {
+    let filename = format!("{filename}.answers");
+    parse_expected(&filename, sidecar::load(&filename)?)
+}
+
+fn parse_expected(filename: &str, entries: Vec<Entry>) -> Result<Expected> {
+    let mut expected = Expected::default();
+    for entry in entries {
+        let part = match entry.name.as_str() {
+            "part1" => &mut expected.part1,
+            "part2" => &mut expected.part2,
+            name => bail!(
+                "{filename}:{}: expected 'part1' or 'part2', found '{name}'",
+                entry.line
+            ),
+        };
+        *part = Some(entry.value);
+    }
+    Ok(expected)
+}
+
+fn run_solution_file<S>(
+    filename: &str,
+    context: &RunContext,
+    iterations: usize,
+) -> Result<(Answers, Timings)>
+where
+    S: Solution + TryFrom<std::io::BufReader<std::fs::File>, Error = ParseError> + std::fmt::Debug,
+{
+    let mut answers = None;
+    let mut samples = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+    for iteration in 0..iterations.max(1) {
+        let start = Instant::now();
+        let mut solution =
+            load::<S>(filename).with_context(|| format!("failed to load '{filename}'"))?;
+        let parsed = Instant::now();
+        solution.analyse(context);
+        let analysed = Instant::now();
+        // A failing part still leaves the other to be answered
+        let part1 = solution
+            .answer_part1(context)
+            .map(Into::into)
+            .context("part1 failed");
+        let solved_part1 = Instant::now();
+        let part2 = solution
+            .answer_part2(context)
+            .map(Into::into)
+            .context("part2 failed");
+        let solved_part2 = Instant::now();
+
+        for (samples, duration) in samples.iter_mut().zip([
+            parsed - start,
+            analysed - parsed,
+            solved_part1 - analysed,
+            solved_part2 - solved_part1,
+        ]) {
+            samples.push(duration);
+        }
+        if iteration == 0 {
+            info!(
+                "{}{} {}: {:?}",
+                Paint::mask("🎄 "),
+                Paint::bold(&Paint::green(filename)),
+                Paint::bold(&Paint::yellow("solution")),
+                solution
+            );
+            for (name, part) in [("part1", &part1), ("part2", &part2)] {
+                match part {
+                    Ok(answer) => info!(
+                        "{}{name} answer is {}",
+                        Paint::mask("🎅 "),
+                        Paint::bold(&Paint::red(answer))
+                    ),
+                    Err(e) => error!(
+                        "{}Failed running against '{}': {:#}",
+                        Paint::mask("🎅 "),
+                        filename,
+                        e
+                    ),
+                }
+            }
+            answers = Some(Answers { part1, part2 });
+        }
+    }
+
+    let [parse, analyse, part1, part2] = samples.map(Timing::from_samples);
+    let timings = Timings {
+        parse,
+        analyse,
+        part1,
+        part2,
+    };
     info!(
-        "{}part2 answer is {}",
+        "{}{} took {:?} (parse {:?}, analyse {:?}, part1 {:?}, part2 {:?})",
         Paint::mask("🎅 "),
-        Paint::bold(&Paint::red(
-            &solution.answer_part2(is_full).context("part2 failed")?
-        ))
+        Paint::bold(&Paint::green(filename)),
+        timings.total(),
+        timings.parse.median,
+        timings.analyse.median,
+        timings.part1.median,
+        timings.part2.median
     );
+    Ok((answers.unwrap(), timings))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn expected() {
+        let filename = "day1.sample.answers";
+        let contents = "# comment\n\npart1: 142\npart2:  281 \n";
+        let expected = parse_expected(filename, sidecar::parse(filename, contents).unwrap());
+        let expected = expected.unwrap();
+        assert_eq!(Some("142"), expected.part1.as_deref());
+        assert_eq!(Some("281"), expected.part2.as_deref());
+        let entries = sidecar::parse(filename, "part3: 1").unwrap();
+        assert!(parse_expected(filename, entries).is_err());
+        assert!(sidecar::parse(filename, "part1").is_err());
+
+        let contents = "part1: |\n  #..\n  .#.\npart2: 3\n";
+        let expected = parse_expected(filename, sidecar::parse(filename, contents).unwrap());
+        let expected = expected.unwrap();
+        assert_eq!(Some("#..\n.#."), expected.part1.as_deref());
+        assert_eq!(Some("3"), expected.part2.as_deref());
+    }
 
-    Ok(())
+    #[test]
+    fn verdicts() {
+        let report = Report {
+            name: "day1".to_string(),
+            suffix: "sample".to_string(),
+            result: Ok(Answers {
+                part1: Ok(Answer::from(142u32)),
+                part2: Ok(Answer::from(280u32)),
+            }),
+            expected: Expected {
+                part1: Some("142".to_string()),
+                part2: Some("281".to_string()),
+            },
+            timings: None,
+        };
+        assert_eq!([Verdict::Pass, Verdict::Fail], report.verdicts());
+        assert!(report.is_failure());
+
+        // One part failing leaves the other's verdict alone
+        let report = Report {
+            result: Ok(Answers {
+                part1: Ok(Answer::from(142u32)),
+                part2: Err(anyhow::anyhow!("failed")),
+            }),
+            expected: Expected {
+                part1: Some("142".to_string()),
+                part2: None,
+            },
+            ..report
+        };
+        assert_eq!([Verdict::Pass, Verdict::Unknown], report.verdicts());
+
+        let report = Report {
+            result: Err(anyhow::anyhow!("failed")),
+            expected: Expected {
+                part1: None,
+                part2: Some("281".to_string()),
+            },
+            ..report
+        };
+        assert_eq!([Verdict::Unknown, Verdict::Fail], report.verdicts());
+    }
+
+    #[test]
+    fn failures() {
+        let passed = || Answers {
+            part1: Ok(Answer::from(142u32)),
+            part2: Ok(Answer::from(281u32)),
+        };
+        let report = Report {
+            name: "day1".to_string(),
+            suffix: "sample".to_string(),
+            result: Ok(passed()),
+            expected: Expected::default(),
+            timings: None,
+        };
+        assert!(!report.is_failure());
+
+        // Nothing was expected, but a part errored
+        let report = Report {
+            result: Ok(Answers {
+                part2: Err(anyhow::anyhow!("failed")),
+                ..passed()
+            }),
+            ..report
+        };
+        assert!(report.is_failure());
+
+        // A malformed answers file, or an input which failed to load
+        let report = Report {
+            result: Err(anyhow::anyhow!("bad answers file")),
+            ..report
+        };
+        assert_eq!([Verdict::Unknown, Verdict::Unknown], report.verdicts());
+        assert!(report.is_failure());
+    }
 }
@@ -0,0 +1,60 @@
+use std::io::ErrorKind;
+
+use anyhow::{bail, Context, Result};
+
+/// A `name: value` line of a sidecar file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Entry {
+    pub line: usize,
+    pub name: String,
+    pub value: String,
+}
+
+/// Read the entries of the sidecar file `filename`, which sits alongside an
+/// input file. A missing sidecar file has no entries.
+pub(crate) fn load(filename: &str) -> Result<Vec<Entry>> {
+    match std::fs::read_to_string(filename) {
+        Ok(contents) => parse(filename, &contents),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("failed to read '{filename}'")),
+    }
+}
+
+/// Parse `name: value` lines, ignoring blank lines and lines starting with `#`.
+///
+/// A value of `|` starts a multi-line value, made up of the indented lines
+/// which follow it.
+pub(crate) fn parse(filename: &str, contents: &str) -> Result<Vec<Entry>> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut block = false;
+    for (id, line) in contents.lines().enumerate() {
+        if block && line.starts_with([' ', '\t']) {
+            let entry = entries.last_mut().unwrap();
+            if !entry.value.is_empty() {
+                entry.value.push('\n');
+            }
+            entry.value.push_str(line.trim());
+            continue;
+        }
+        block = false;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once(':') {
+            Some((name, value)) if !name.trim().is_empty() => {
+                block = value.trim() == "|";
+                entries.push(Entry {
+                    line: id + 1,
+                    name: name.trim().to_owned(),
+                    value: if block { "" } else { value.trim() }.to_owned(),
+                })
+            }
+            _ => bail!(
+                "{filename}:{}: expected '<name>: <value>', found '{line}'",
+                id + 1
+            ),
+        }
+    }
+    Ok(entries)
+}
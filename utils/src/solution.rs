@@ -1,15 +1,18 @@
+use crate::{parse::ParseError, Answer, RunContext};
+
 pub trait Solution {
-    type Result;
+    type Part1: Into<Answer>;
+    type Part2: Into<Answer>;
 
-    fn analyse(&mut self, is_full: bool);
+    fn analyse(&mut self, context: &RunContext);
 
-    fn answer_part1(&self, is_full: bool) -> Self::Result;
-    fn answer_part2(&self, is_full: bool) -> Self::Result;
+    fn answer_part1(&self, context: &RunContext) -> anyhow::Result<Self::Part1>;
+    fn answer_part2(&self, context: &RunContext) -> anyhow::Result<Self::Part2>;
 }
 
-pub fn load<T: Solution + TryFrom<std::io::BufReader<std::fs::File>, Error = std::io::Error>>(
+pub fn load<T: Solution + TryFrom<std::io::BufReader<std::fs::File>, Error = ParseError>>(
     filename: &str,
-) -> std::io::Result<T> {
+) -> Result<T, ParseError> {
     let file = std::fs::File::open(filename)?;
 
     let reader = std::io::BufReader::new(file);
@@ -0,0 +1,97 @@
+use std::{fmt::Display, time::Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Analyse,
+    Part1,
+    Part2,
+}
+impl Phase {
+    pub const ALL: [Phase; 4] = [Self::Parse, Self::Analyse, Self::Part1, Self::Part2];
+}
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse => write!(f, "parse"),
+            Self::Analyse => write!(f, "analyse"),
+            Self::Part1 => write!(f, "part1"),
+            Self::Part2 => write!(f, "part2"),
+        }
+    }
+}
+
+/// Wall time statistics for one phase over a number of iterations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timing {
+    pub iterations: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+impl Timing {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let iterations = samples.len();
+        if iterations == 0 {
+            return Self::default();
+        }
+        let median = if iterations.is_multiple_of(2) {
+            (samples[iterations / 2 - 1] + samples[iterations / 2]) / 2
+        } else {
+            samples[iterations / 2]
+        };
+        Self {
+            iterations,
+            min: samples[0],
+            median,
+            max: samples[iterations - 1],
+        }
+    }
+}
+
+/// Timings for each phase of running a solution against one input file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Timing,
+    pub analyse: Timing,
+    pub part1: Timing,
+    pub part2: Timing,
+}
+impl Timings {
+    pub fn get(&self, phase: Phase) -> &Timing {
+        match phase {
+            Phase::Parse => &self.parse,
+            Phase::Analyse => &self.analyse,
+            Phase::Part1 => &self.part1,
+            Phase::Part2 => &self.part2,
+        }
+    }
+
+    /// Sum of the median time of every phase.
+    pub fn total(&self) -> Duration {
+        Phase::ALL.iter().map(|phase| self.get(*phase).median).sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_samples() {
+        let timing = Timing::from_samples(
+            [5, 1, 3, 9]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect(),
+        );
+        assert_eq!(4, timing.iterations);
+        assert_eq!(Duration::from_millis(1), timing.min);
+        assert_eq!(Duration::from_millis(4), timing.median);
+        assert_eq!(Duration::from_millis(9), timing.max);
+
+        let timing = Timing::from_samples(vec![Duration::from_millis(7)]);
+        assert_eq!(Duration::from_millis(7), timing.median);
+    }
+}
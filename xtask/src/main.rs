@@ -16,8 +16,8 @@ fn setup() -> AnyResult<()> {
     // Copy template to new directory
     println!("* copy 'template' to '{entry}'");
     xtaskops::ops::copy_contents("template", &entry, false)?;
-    // Replace 'template' with entry name in Cargo.toml, src/main.rs
-    for filename in ["Cargo.toml", "src/main.rs"]
+    // Replace 'template' with entry name in Cargo.toml
+    for filename in ["Cargo.toml"]
         .iter()
         .map(|name| PathBuf::from(&entry).join(name))
     {
@@ -30,11 +30,36 @@ fn setup() -> AnyResult<()> {
     println!("* setup Cargo.toml");
 
     let workspace_toml_contents = std::fs::read_to_string("Cargo.toml")?;
-    let mut toml = workspace_toml_contents.parse::<toml_edit::Document>()?;
+    let mut toml = workspace_toml_contents.parse::<toml_edit::DocumentMut>()?;
     toml["workspace"]["members"]
         .as_array_mut()
         .context("read workspace members")?
-        .push(entry);
+        .push(&entry);
     std::fs::write("Cargo.toml", toml.to_string())?;
+
+    register_with_runner(&entry)
+}
+
+fn register_with_runner(entry: &str) -> AnyResult<()> {
+    let day = entry
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .parse::<u32>()
+        .with_context(|| format!("'{entry}' does not end with a day number"))?;
+
+    // Add entry as a dependency of the runner
+    println!("* setup aoc/Cargo.toml");
+    let runner_toml_contents = std::fs::read_to_string("aoc/Cargo.toml")?;
+    let mut toml = runner_toml_contents.parse::<toml_edit::DocumentMut>()?;
+    let mut dependency = toml_edit::Table::new();
+    dependency["path"] = toml_edit::value(format!("../{entry}"));
+    toml["dependencies"][entry] = toml_edit::Item::Table(dependency);
+    std::fs::write("aoc/Cargo.toml", toml.to_string())?;
+
+    // Add entry to the runner's registry of days
+    println!("* setup aoc/src/days.rs");
+    let days = std::fs::read_to_string("aoc/src/days.rs")?;
+    let end = days.rfind('}').context("find end of day registry")?;
+    let days = format!("{}    {day} => {entry},\n{}", &days[..end], &days[end..]);
+    std::fs::write("aoc/src/days.rs", days)?;
     Ok(())
 }